@@ -0,0 +1,145 @@
+/// The limits a detected signal has to satisfy before it is accepted by a decoder.
+///
+/// Standards specify an acceptance and a rejection limit for most parameters: Signals within the
+/// first one have to be accepted, signals beyond the second one have to be rejected. Decoders
+/// decide in the middle between both limits to leave room for measurement errors.
+///
+/// The default limits do not restrict the detection at all.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AcceptanceLimits {
+    /// The relative deviation of a frequency which has to be accepted.
+    pub frequency_accept: f64,
+    /// The relative deviation of a frequency which has to be rejected.
+    pub frequency_reject: f64,
+    /// The maximal level in dB the higher frequency may be louder than the lower one.
+    pub normal_twist: f64,
+    /// The maximal level in dB the lower frequency may be louder than the higher one.
+    pub reverse_twist: f64,
+    /// The duration of a signal in seconds which has to be accepted.
+    pub duration_accept: f64,
+    /// The duration of a signal in seconds which has to be rejected.
+    pub duration_reject: f64,
+    /// The minimal ratio in dB between the power of both frequencies and the remaining power.
+    pub min_snr: f64,
+}
+
+impl AcceptanceLimits {
+    /// The limits according to ITU-T Q.24.
+    /// # Example
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use dtmf::Signal;
+    /// use dtmf::decoder::{AcceptanceLimits, StreamingDecoder};
+    ///
+    /// // Signals whose frequencies deviate by the given fraction and whose higher tone is 6 dB
+    /// // louder than the lower one.
+    /// let detect = |signal: Signal, deviation: f64| {
+    ///     let (low, high) = signal.frequencies();
+    ///     let (low, high) = (low as f64 * (1. + deviation), high as f64 * (1. + deviation));
+    ///     let samples = (0..1200).map(|i| match i < 800 {
+    ///         true => {
+    ///             let time = i as f64 / 8000.;
+    ///             0.25 * (2. * PI * low * time).sin() + 0.5 * (2. * PI * high * time).sin()
+    ///         }
+    ///         false => 0.,
+    ///     });
+    ///     let mut decoder = StreamingDecoder::with_limits(8000., AcceptanceLimits::q24());
+    ///     decoder.process(samples).unwrap().iter().map(|event| event.signal()).collect::<Vec<_>>()
+    /// };
+    ///
+    /// for &signal in Signal::iter() {
+    ///     assert_eq!(detect(signal, -0.015), vec![signal]);
+    ///     assert_eq!(detect(signal, 0.015), vec![signal]);
+    ///     assert!(detect(signal, -0.035).is_empty());
+    ///     assert!(detect(signal, 0.035).is_empty());
+    /// }
+    /// ```
+    pub fn q24() -> AcceptanceLimits {
+        AcceptanceLimits {
+            frequency_accept: 0.015,
+            frequency_reject: 0.035,
+            normal_twist: 8.0,
+            reverse_twist: 4.0,
+            duration_accept: 0.04,
+            duration_reject: 0.02,
+            min_snr: 15.0,
+        }
+    }
+
+    /// The limits according to ANSI T1.401 as used in North America.
+    pub fn ansi() -> AcceptanceLimits {
+        AcceptanceLimits {
+            duration_reject: 0.023,
+            ..AcceptanceLimits::q24()
+        }
+    }
+
+    /// The limits according to ETSI ES 201 235-3 as used in Europe.
+    pub fn etsi() -> AcceptanceLimits {
+        AcceptanceLimits {
+            normal_twist: 6.0,
+            reverse_twist: 6.0,
+            duration_reject: 0.025,
+            ..AcceptanceLimits::q24()
+        }
+    }
+
+    /// Checks whether the relative deviation of a frequency is acceptable.
+    /// # Example
+    /// ```
+    /// use dtmf::decoder::AcceptanceLimits;
+    ///
+    /// let limits = AcceptanceLimits::q24();
+    /// assert!(limits.accepts_deviation(-0.015));
+    /// assert!(!limits.accepts_deviation(0.035));
+    /// ```
+    pub fn accepts_deviation(&self, deviation: f64) -> bool {
+        deviation.abs() < (self.frequency_accept + self.frequency_reject) / 2.0
+    }
+
+    /// Checks whether the level in dB of the higher frequency relative to the lower one is
+    /// acceptable.
+    /// # Example
+    /// ```
+    /// use dtmf::decoder::AcceptanceLimits;
+    ///
+    /// let limits = AcceptanceLimits::q24();
+    /// assert!(limits.accepts_twist(7.5));
+    /// assert!(!limits.accepts_twist(-4.5));
+    /// ```
+    pub fn accepts_twist(&self, twist: f64) -> bool {
+        -self.reverse_twist <= twist && twist <= self.normal_twist
+    }
+
+    /// Checks whether the duration of a signal in seconds is acceptable.
+    /// # Example
+    /// ```
+    /// use dtmf::decoder::AcceptanceLimits;
+    ///
+    /// let limits = AcceptanceLimits::ansi();
+    /// assert!(limits.accepts_duration(0.04));
+    /// assert!(!limits.accepts_duration(0.023));
+    /// ```
+    pub fn accepts_duration(&self, duration: f64) -> bool {
+        duration > (self.duration_accept + self.duration_reject) / 2.0
+    }
+
+    /// Checks whether the signal-to-noise ratio in dB is acceptable.
+    pub fn accepts_snr(&self, snr: f64) -> bool {
+        snr >= self.min_snr
+    }
+}
+
+impl Default for AcceptanceLimits {
+    fn default() -> AcceptanceLimits {
+        AcceptanceLimits {
+            frequency_accept: f64::INFINITY,
+            frequency_reject: f64::INFINITY,
+            normal_twist: f64::INFINITY,
+            reverse_twist: f64::INFINITY,
+            duration_accept: 0.0,
+            duration_reject: 0.0,
+            min_snr: f64::NEG_INFINITY,
+        }
+    }
+}
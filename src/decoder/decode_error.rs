@@ -0,0 +1,48 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+/// An error occurring while decoding samples.
+/// # Example
+/// ```
+/// use dtmf::decoder::{decode_signal, DecodeError};
+///
+/// assert_eq!(decode_signal(Vec::<f64>::new(), 8000.), Err(DecodeError::Empty));
+/// assert_eq!(decode_signal(vec![0.; 40], 8000.), Err(DecodeError::TooShort(80)));
+/// assert_eq!(decode_signal(vec![0., 0., ::std::f64::NAN], 8000.),
+///            Err(DecodeError::InvalidSample(2)));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DecodeError {
+    /// The given input was empty.
+    Empty,
+    /// The sample at the given position is not a finite number.
+    InvalidSample(usize),
+    /// The given input is shorter than the given number of samples required for decoding.
+    TooShort(usize),
+    /// The message ends within the signal starting at the given sample.
+    Truncated(usize),
+    /// The frame does not contain the given number of samples the decoder was created for.
+    WrongLength(usize),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter) -> FormatResult {
+        match *self {
+            DecodeError::Empty => write!(f, "no samples given"),
+            DecodeError::InvalidSample(position) => {
+                write!(f, "the sample at position {} is not a finite number", position)
+            }
+            DecodeError::TooShort(required) => {
+                write!(f, "at least {} samples are required for decoding", required)
+            }
+            DecodeError::Truncated(position) => {
+                write!(f, "the signal starting at sample {} is truncated", position)
+            }
+            DecodeError::WrongLength(expected) => {
+                write!(f, "the frame does not contain exactly {} samples", expected)
+            }
+        }
+    }
+}
+
+impl Error for DecodeError {}
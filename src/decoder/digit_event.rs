@@ -0,0 +1,87 @@
+use Signal;
+
+/// A signal detected by a decoder together with the position where it occurred.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DigitEvent {
+    signal: Signal,
+    start_sample: usize,
+    end_sample: usize,
+    sample_rate: f64,
+}
+
+impl DigitEvent {
+    /// Creates a new event of a signal, which starts at the given sample and ends before the
+    /// other one.
+    pub fn new(signal: Signal,
+               start_sample: usize,
+               end_sample: usize,
+               sample_rate: f64)
+               -> DigitEvent {
+        DigitEvent {
+            signal,
+            start_sample,
+            end_sample,
+            sample_rate,
+        }
+    }
+
+    /// The detected signal.
+    pub fn signal(&self) -> Signal {
+        self.signal
+    }
+
+    /// The offset of the first sample of the signal in the stream.
+    pub fn start_sample(&self) -> usize {
+        self.start_sample
+    }
+
+    /// The offset of the first sample after the signal in the stream.
+    pub fn end_sample(&self) -> usize {
+        self.end_sample
+    }
+
+    /// The sample rate of the stream the signal was detected in.
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// The start of the signal in seconds.
+    /// # Example
+    /// ```
+    /// use dtmf::Signal;
+    /// use dtmf::decoder::DigitEvent;
+    ///
+    /// let event = DigitEvent::new(Signal::A, 4000, 6000, 8000.);
+    /// assert_eq!(event.start(), 0.5);
+    /// assert_eq!(event.end(), 0.75);
+    /// assert_eq!(event.duration(), 0.25);
+    /// ```
+    pub fn start(&self) -> f64 {
+        self.start_sample as f64 / self.sample_rate
+    }
+
+    /// The end of the signal in seconds.
+    pub fn end(&self) -> f64 {
+        self.end_sample as f64 / self.sample_rate
+    }
+
+    /// The duration of the signal in seconds.
+    pub fn duration(&self) -> f64 {
+        self.end() - self.start()
+    }
+
+    /// The duration of the gap between the end of this signal and the start of a later one in
+    /// seconds.
+    /// # Example
+    /// ```
+    /// use dtmf::Signal;
+    /// use dtmf::decoder::DigitEvent;
+    ///
+    /// let first = DigitEvent::new(Signal::digit(1).unwrap(), 0, 2000, 8000.);
+    /// let second = DigitEvent::new(Signal::digit(2).unwrap(), 4000, 6000, 8000.);
+    /// assert_eq!(first.gap_to(&second), 0.25);
+    /// ```
+    pub fn gap_to(&self, later: &DigitEvent) -> f64 {
+        later.start() - self.end()
+    }
+}
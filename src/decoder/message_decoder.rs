@@ -1,8 +1,15 @@
+use sample::{Sample, ToSample};
+
 use Message;
 
-use super::decode_signal;
+use super::{DecodeError, Decoder, DigitEvent};
 
-/// Decodes a message.
+/// Decodes a message and returns the positions of its signals.
+///
+/// Signals are expected at the positions given by the durations of the message. Positions which
+/// do not contain a signal are skipped. If the samples end within a signal, the previous signals
+/// are still added to the message and the truncation is reported. A remainder which is too short
+/// for a signal, but does not contain one, like trailing silence, is ignored.
 /// # Example
 /// ```
 /// use dtmf::{Message, Signal};
@@ -16,42 +23,114 @@ use super::decode_signal;
 /// let mut target_message = Message::default();
 ///
 /// let data = MessageEncoder::new(&message, 48000.);
-/// decode_message(data.map(|x| x[0]), &mut target_message, 48000.);
+/// let events = decode_message(data.map(|x| x[0]), &mut target_message, 48000.).unwrap();
 ///
 /// assert_eq!(message, target_message);
+/// assert_eq!(events[1].signal(), Signal::B);
+/// assert_eq!(events[1].start(), 1.0);
+/// assert_eq!(events[1].end(), 1.7);
 /// ```
-pub fn decode_message<S>(sample_iter: S, message: &mut Message, sample_rate: f64)
-    where S: IntoIterator<Item = f64>,
-          S::IntoIter: ExactSizeIterator
+/// A message which ends too early is detected:
+/// ```
+/// use dtmf::{Message, Signal};
+/// use dtmf::encoder::MessageEncoder;
+/// use dtmf::decoder::{decode_message, DecodeError};
+///
+/// let mut message = Message::default();
+/// message.enqueue(Signal::A);
+/// message.enqueue(Signal::B);
+///
+/// let mut target_message = Message::default();
+///
+/// let data = MessageEncoder::new(&message, 8000.).take(10000).map(|x| x[0]).collect::<Vec<_>>();
+/// let result = decode_message(data, &mut target_message, 8000.);
+///
+/// assert_eq!(result, Err(DecodeError::Truncated(8000)));
+/// assert_eq!(target_message.len(), 1);
+/// ```
+/// Trailing silence is not a truncated signal:
+/// ```
+/// use dtmf::Message;
+/// use dtmf::encoder::MessageEncoder;
+/// use dtmf::decoder::decode_message;
+///
+/// let message = "12".parse::<Message>().unwrap();
+/// let mut data = MessageEncoder::new(&message, 8000.).map(|x| x[0]).collect::<Vec<_>>();
+/// data.extend_from_slice(&[0.; 4000]);
+///
+/// let mut target_message = Message::default();
+/// let events = decode_message(data, &mut target_message, 8000.).unwrap();
+///
+/// assert_eq!(events.len(), 2);
+/// assert_eq!(target_message.to_string(), "12");
+/// ```
+pub fn decode_message<S>(sample_iter: S,
+                         message: &mut Message,
+                         sample_rate: f64)
+                         -> Result<Vec<DigitEvent>, DecodeError>
+    where S: IntoIterator,
+          S::IntoIter: ExactSizeIterator,
+          S::Item: Sample + ToSample<f64>
 {
     let mut samples = sample_iter.into_iter();
-    let mut length = samples.len();
+    let length = samples.len();
+    if length == 0 {
+        return Err(DecodeError::Empty);
+    }
 
     let signal_duration = (message.signal_duration() * sample_rate) as usize;
     let silence_duration = (message.silence_duration() * sample_rate) as usize;
+    let decoder = Decoder::new(sample_rate, signal_duration)?;
 
-    let mut first_signal = true;
-    while length > 0 {
+    let mut events = Vec::new();
+    let mut frame = Vec::with_capacity(signal_duration);
+    let mut position = 0;
+    while position < length {
 
-        // Create a stream of samples
-        let samples = samples.by_ref()
-            .skip(match first_signal {
-                true => {
-                    first_signal = false;
-                    0
+        // Each signal but the first one is preceded by silence
+        let skip = match position {
+            0 => 0,
+            _ => silence_duration,
+        };
+        if length - position < skip + signal_duration {
+            // The remainder is only truncated, if it contains the start of a signal
+            let tail = samples.skip(skip).collect::<Vec<_>>();
+            let decoder = match Decoder::new(sample_rate, tail.len()) {
+                Ok(decoder) => decoder,
+                Err(_) => return Ok(events),
+            };
+            return match decoder.analyze(&tail) {
+                Ok(report) if report.tones().is_some() => {
+                    Err(DecodeError::Truncated(position + skip))
                 }
-                false => {
-                    length -= silence_duration;
-                    silence_duration
+                Ok(_) => Ok(events),
+                Err(DecodeError::InvalidSample(offset)) => {
+                    Err(DecodeError::InvalidSample(position + skip + offset))
                 }
-            })
-            .take(signal_duration);
-
-        // Decode each signal
-        let signal = decode_signal(samples, sample_rate);
+                Err(error) => Err(error),
+            };
+        }
+        position += skip;
 
-        // Add the signal
-        message.enqueue(signal);
-        length -= signal_duration;
+        // Decode each signal and add it, iff there is one
+        frame.clear();
+        frame.extend(samples.by_ref().skip(skip).take(signal_duration));
+        match decoder.decode(&frame) {
+            Ok(Some(signal)) => {
+                message.enqueue(signal);
+                events.push(DigitEvent::new(signal,
+                                            position,
+                                            position + signal_duration,
+                                            sample_rate));
+            }
+            Ok(None) => {}
+            Err(DecodeError::InvalidSample(offset)) => {
+                return Err(DecodeError::InvalidSample(position + offset))
+            }
+            Err(error) => return Err(error),
+        }
+        position += signal_duration;
     }
+
+    Ok(events)
 }
@@ -1,5 +1,13 @@
 mod signal_decoder;
 mod message_decoder;
+mod streaming_decoder;
+mod digit_event;
+mod acceptance_limits;
+mod decode_error;
 
-pub use self::signal_decoder::decode_signal;
+pub use self::signal_decoder::{decode_signal, analyze_signal, Decoder, DetectionReport};
 pub use self::message_decoder::decode_message;
+pub use self::streaming_decoder::StreamingDecoder;
+pub use self::digit_event::DigitEvent;
+pub use self::acceptance_limits::AcceptanceLimits;
+pub use self::decode_error::DecodeError;
@@ -1,9 +1,36 @@
-use std::cmp::Ordering;
-use std::collections::HashSet;
+use sample::{Sample, ToSample};
 
 use Signal;
 
+use super::DecodeError;
+
+/// The duration in seconds a frame requires at least to distinguish the frequencies.
+const MIN_FRAME_DURATION: f64 = 0.01;
+/// The mean power of samples below which they are treated as silence.
+const MIN_POWER: f64 = 1e-6;
+/// The fraction of the energy which has to be contained in the two detected tones.
+const MIN_TONE_RATIO: f64 = 0.5;
+/// The maximal relative deviation of a tone from its DTMF frequency which is searched, such that
+/// it could not be confused with the neighbouring frequencies.
+const MAX_DEVIATION: f64 = 0.05;
+/// The relative deviation of a tone from its DTMF frequency, which has to be rejected by all
+/// standards. Harmonics of voice often fall between the searched and this deviation.
+const REJECTED_DEVIATION: f64 = 0.035;
+/// The maximal signal-to-noise ratio in dB which is reported, as the remaining power of clean
+/// signals is only limited by the precision of the measurement.
+const MAX_SNR: f64 = 100.0;
+/// The maximal power in dB of the second harmonics relative to the lower and the higher tone.
+const MAX_HARMONICS: (f64, f64) = (-20.0, -20.0);
+/// The maximal duration in seconds used to measure the tones, such that deviating frequencies are
+/// found within a few bins. Longer frames are measured within their strongest part.
+const MAX_MEASUREMENT_DURATION: f64 = 0.025;
+/// The frequencies used by DTMF in ascending order.
+const FREQUENCIES: [u16; 8] = [697, 770, 852, 941, 1209, 1336, 1477, 1633];
+
 /// Decodes a signal from a stream of samples.
+///
+/// Samples which are silent or whose energy is not mostly contained in two DTMF frequencies, like
+/// noise, speech or music, do not contain a signal.
 /// # Example
 /// ```
 /// use dtmf::encoder::SignalEncoder;
@@ -11,45 +38,588 @@ use Signal;
 /// use dtmf::Signal;
 ///
 /// for &signal in Signal::iter() {
-///     let data = SignalEncoder::new(signal, 48000.).unwrap().take(12000).map(|x| x[0]).collect::<Vec<f64>>();
-///     assert_eq!(decode_signal(data, 48000.), signal);
+///     let data = SignalEncoder::new(signal, 48000.).take(12000).map(|x| x[0]).collect::<Vec<f64>>();
+///     assert_eq!(decode_signal(data, 48000.), Ok(Some(signal)));
 /// }
+///
+/// // Neither silence nor a single tone is a signal.
+/// let tone = (0..12000).map(|i| (i as f64 * 2. * ::std::f64::consts::PI * 1000. / 48000.).sin());
+/// assert_eq!(decode_signal(tone, 48000.), Ok(None));
+/// assert_eq!(decode_signal(vec![0.; 12000], 48000.), Ok(None));
+///
+/// // Samples of other formats like 16-bit PCM are supported as well.
+/// let data = SignalEncoder::new(Signal::digit(7).unwrap(), 8000.).take(400).map(|x| (x[0] * 32767.) as i16);
+/// assert_eq!(decode_signal(data, 8000.), Ok(Some(Signal::digit(7).unwrap())));
+///
+/// // Signals do not have to start at the beginning of the samples.
+/// let data = ::std::iter::repeat(0.)
+///     .take(100)
+///     .chain(SignalEncoder::new(Signal::digit(5).unwrap(), 8000.).take(1600).map(|x| x[0]));
+/// assert_eq!(decode_signal(data, 8000.), Ok(Some(Signal::digit(5).unwrap())));
 /// ```
-pub fn decode_signal<T>(samples: T, sample_rate: f64) -> Signal
-    where T: IntoIterator<Item = f64>,
-          T::IntoIter: ExactSizeIterator
+pub fn decode_signal<T>(samples: T, sample_rate: f64) -> Result<Option<Signal>, DecodeError>
+    where T: IntoIterator,
+          T::Item: Sample + ToSample<f64>
 {
-    // Separate higher and lower frequencies
-    let low_freq: HashSet<u16> = [697, 770, 852, 941].iter().cloned().collect();
-    let high_freq: HashSet<u16> = [1209, 1336, 1477, 1633].iter().cloned().collect();
+    analyze_signal(samples, sample_rate).map(|report| report.signal())
+}
 
-    // Apply the goerzel algorithm to all frequencies
-    let mut bins = GoertzelBin::apply_goerzel(samples.into_iter(),
-                                              sample_rate,
-                                              low_freq.union(&high_freq).cloned());
+/// Analyses a stream of samples and reports the measurements the detection is based on.
+/// # Example
+/// ```
+/// use dtmf::encoder::SignalEncoder;
+/// use dtmf::decoder::analyze_signal;
+/// use dtmf::Signal;
+///
+/// let data = SignalEncoder::new(Signal::digit(5).unwrap(), 8000.).take(400).map(|x| x[0]);
+/// let report = analyze_signal(data, 8000.).expect("Valid samples");
+///
+/// assert_eq!(report.signal(), Some(Signal::digit(5).unwrap()));
+/// assert_eq!(report.frequencies(), (770, 1336));
+/// assert!(report.power(770).unwrap() > 100. * report.power(697).unwrap());
+/// assert!(report.confidence() > 0.95 && report.confidence() <= 1.0);
+/// assert!(report.snr() > 15. && report.snr().is_finite());
+///
+/// // The encoder uses a higher amplitude for the higher frequency.
+/// assert!(report.twist() > 1.5 && report.twist() < 2.5);
+///
+/// // The tones are measured where they are, even after a leading silence.
+/// let data = ::std::iter::repeat(0.)
+///     .take(300)
+///     .chain(SignalEncoder::new(Signal::digit(5).unwrap(), 8000.).take(1600).map(|x| x[0]));
+/// let report = analyze_signal(data, 8000.).expect("Valid samples");
+/// assert!(report.harmonics().0 < -20. && report.harmonics().1 < -20.);
+/// assert!(report.confidence() > 0.95);
+/// assert!(report.twist() > 1.5 && report.twist() < 2.5);
+/// ```
+pub fn analyze_signal<T>(samples: T, sample_rate: f64) -> Result<DetectionReport, DecodeError>
+    where T: IntoIterator,
+          T::Item: Sample + ToSample<f64>
+{
+    let samples = samples.into_iter().collect::<Vec<T::Item>>();
+    check(&samples)?;
+    Decoder::new(sample_rate, samples.len())?.analyze(&samples)
+}
 
-    // Sorts the bins by their power
-    bins.sort();
+/// Returns the number of samples a frame requires at least to be decoded.
+pub fn min_frame_length(sample_rate: f64) -> usize {
+    (sample_rate * MIN_FRAME_DURATION).ceil() as usize
+}
 
-    // Find the high frequence with the most power
-    let low_freq = bins.iter()
-        .rev()
-        .map(|bin| bin.frequency())
-        .find(|freq| low_freq.contains(freq))
-        .expect("Missing lower frequency");
+/// Checks whether a frame could be analysed.
+fn check<S>(frame: &[S]) -> Result<(), DecodeError>
+    where S: Sample + ToSample<f64>
+{
+    if frame.is_empty() {
+        return Err(DecodeError::Empty);
+    }
 
-    // Find the high frequence with the most power
-    let high_freq = bins.iter()
-        .rev()
-        .map(|bin| bin.frequency())
-        .find(|freq| high_freq.contains(freq))
-        .expect("Missing higher frequency");
+    match frame.iter().map(value).position(|sample| !sample.is_finite()) {
+        Some(position) => Err(DecodeError::InvalidSample(position)),
+        None => Ok(()),
+    }
+}
 
-    Signal::from_frequencies((low_freq, high_freq)).expect("Valid frequencies")
+/// The measurements a detection of a signal within a frame is based on.
+///
+/// The powers and the energy cover the whole frame, while the tones are measured within the 25 ms
+/// of the frame containing the most energy.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DetectionReport {
+    signal: Option<Signal>,
+    tones: Option<Signal>,
+    powers: [(u16, f64); 8],
+    energy: f64,
+    frequencies: (u16, u16),
+    ratio: f64,
+    twist: f64,
+    deviations: (f64, f64),
+    harmonics: (f64, f64),
 }
 
-/// An bin for the goertzel algorithm which could be sorted by its power.
-struct GoertzelBin {
+impl DetectionReport {
+    /// The detected signal, if there is one.
+    pub fn signal(&self) -> Option<Signal> {
+        self.signal
+    }
+
+    /// The signal whose tones dominate the frame regardless of their harmonics, if there is one.
+    ///
+    /// Frames covering the start or the end of a signal contain artificial harmonics.
+    pub(crate) fn tones(&self) -> Option<Signal> {
+        self.tones
+    }
+
+    /// The power of the eight DTMF frequencies in ascending order.
+    pub fn powers(&self) -> &[(u16, f64)] {
+        &self.powers
+    }
+
+    /// The power of a DTMF frequency, if it is one.
+    pub fn power(&self, freq: u16) -> Option<f64> {
+        self.powers.iter().find(|&&(other, _)| other == freq).map(|&(_, power)| power)
+    }
+
+    /// The total energy of the frame.
+    pub fn energy(&self) -> f64 {
+        self.energy
+    }
+
+    /// The lower and the higher DTMF frequency next to the strongest tones.
+    pub fn frequencies(&self) -> (u16, u16) {
+        self.frequencies
+    }
+
+    /// The level of the higher frequency relative to the lower one in dB.
+    pub fn twist(&self) -> f64 {
+        self.twist
+    }
+
+    /// The estimated relative deviations of the lower and the higher tone from their frequencies.
+    ///
+    /// Deviations are measured up to 5 %, while signals deviating by 3.5 % or more are never
+    /// detected.
+    pub fn deviations(&self) -> (f64, f64) {
+        self.deviations
+    }
+
+    /// The power of the second harmonics of the lower and the higher tone relative to the tones
+    /// in dB.
+    ///
+    /// Pure tones do not have harmonics, while voice usually contains strong ones.
+    /// # Example
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use dtmf::decoder::{decode_signal, Decoder, StreamingDecoder};
+    ///
+    /// // Vowels whose harmonics are close to DTMF frequencies must not be mistaken for signals.
+    /// let vowel = |pitch: f64, formants: [f64; 2]| {
+    ///     (0..2400).map(move |i| {
+    ///         (1..40).map(|k| k as f64 * pitch).filter(|&freq| freq < 3900.).map(|freq| {
+    ///             let resonance = formants.iter()
+    ///                 .map(|formant| 3. / (1. + ((freq - formant) / 80.).powi(2)))
+    ///                 .sum::<f64>();
+    ///             0.02 * (pitch / freq + resonance) * (2. * PI * freq * i as f64 / 8000.).sin()
+    ///         }).sum::<f64>()
+    ///     }).collect::<Vec<f64>>()
+    /// };
+    ///
+    /// let decoder = Decoder::new(8000., 200).unwrap();
+    /// for &(pitch, formants) in &[(171.8, [700., 1200.]), (190.5, [800., 1300.]),
+    ///                             (233.0, [900., 1600.]), (284.0, [900., 1600.])] {
+    ///     let samples = vowel(pitch, formants);
+    ///     assert_eq!(decode_signal(samples.iter().cloned(), 8000.), Ok(None));
+    ///     for frame in samples.chunks(200) {
+    ///         assert_eq!(decoder.decode(frame), Ok(None));
+    ///     }
+    ///
+    ///     let mut decoder = StreamingDecoder::new(8000.);
+    ///     assert!(decoder.process(samples).unwrap().is_empty());
+    ///     assert!(decoder.finish().is_none());
+    /// }
+    /// ```
+    pub fn harmonics(&self) -> (f64, f64) {
+        self.harmonics
+    }
+
+    /// The fraction of the frame energy contained in both tones between 0 and 1.
+    pub fn confidence(&self) -> f64 {
+        self.ratio
+    }
+
+    /// The ratio between the power of both tones and the remaining power in dB, which is at most
+    /// 100 dB.
+    pub fn snr(&self) -> f64 {
+        (10.0 * (self.ratio / (1.0 - self.ratio)).log10()).min(MAX_SNR)
+    }
+}
+
+/// A decoder for frames of a fixed length.
+///
+/// Everything which does not depend on the samples, like the coefficients of the Goertzel bins, is
+/// computed once, such that any number of frames can be analysed without allocating memory.
+/// # Example
+/// ```
+/// use dtmf::encoder::SignalEncoder;
+/// use dtmf::decoder::{Decoder, DecodeError};
+/// use dtmf::Signal;
+///
+/// let decoder = Decoder::new(8000., 200).expect("Long enough frames");
+/// for &signal in Signal::iter() {
+///     let frame = SignalEncoder::new(signal, 8000.).take(200).map(|x| x[0]).collect::<Vec<f64>>();
+///     assert_eq!(decoder.decode(&frame), Ok(Some(signal)));
+/// }
+///
+/// assert_eq!(decoder.decode(&[0.; 100]), Err(DecodeError::WrongLength(200)));
+/// assert_eq!(Decoder::new(8000., 40).err(), Some(DecodeError::TooShort(80)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Decoder {
+    sample_rate: f64,
+    block_size: usize,
+    segment_length: usize,
+    window: Vec<f64>,
+    bins: [GoertzelBin; 8],
+}
+
+impl Decoder {
+    /// Creates a new decoder for frames of the given number of samples, iff they are long enough.
+    pub fn new(sample_rate: f64, block_size: usize) -> Result<Decoder, DecodeError> {
+        let required = min_frame_length(sample_rate);
+        if block_size == 0 || block_size < required {
+            return Err(DecodeError::TooShort(required.max(1)));
+        }
+
+        let segment_length = ((sample_rate * MAX_MEASUREMENT_DURATION) as usize)
+            .max(1)
+            .min(block_size);
+        let mut bins = [GoertzelBin::at(0.0, sample_rate); 8];
+        for (index, &freq) in FREQUENCIES.iter().enumerate() {
+            bins[index] = GoertzelBin::new(freq, sample_rate / block_size as f64, block_size);
+        }
+
+        // The Hann window keeps the other tone from leaking into the search of a tone.
+        let window = (0..segment_length)
+            .map(|index| {
+                let phase = 2.0 * ::std::f64::consts::PI * index as f64 / segment_length as f64;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+
+        Ok(Decoder {
+            sample_rate,
+            block_size,
+            segment_length,
+            window,
+            bins,
+        })
+    }
+
+    /// The sample rate of the frames.
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// The number of samples in each frame.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Decodes the signal within a frame, if there is one.
+    pub fn decode<S>(&self, frame: &[S]) -> Result<Option<Signal>, DecodeError>
+        where S: Sample + ToSample<f64>
+    {
+        self.analyze(frame).map(|report| report.signal())
+    }
+
+    /// Detects the signal within a frame and measures its properties.
+    pub fn analyze<S>(&self, frame: &[S]) -> Result<DetectionReport, DecodeError>
+        where S: Sample + ToSample<f64>
+    {
+        if frame.len() != self.block_size {
+            return Err(DecodeError::WrongLength(self.block_size));
+        }
+        if let Some(position) = frame.iter().map(value).position(|sample| !sample.is_finite()) {
+            return Err(DecodeError::InvalidSample(position));
+        }
+        Ok(self.measure(frame))
+    }
+
+    /// Measures the properties of a frame with the correct length and finite samples.
+    pub(crate) fn measure<S>(&self, frame: &[S]) -> DetectionReport
+        where S: Sample + ToSample<f64>
+    {
+        let len = frame.len() as f64;
+        let mut bins = self.bins;
+        let mut energy = 0.0;
+        for sample in frame.iter().map(value) {
+            energy += sample * sample;
+            for bin in bins.iter_mut() {
+                bin.add_sample(sample);
+            }
+        }
+        let mut powers = [(0, 0.); 8];
+        for (power, bin) in powers.iter_mut().zip(bins.iter()) {
+            *power = (bin.frequency(), bin.calculate());
+        }
+
+        // Measure the tones at their actual frequencies, which might be slightly off. Signals do
+        // not have to start at the beginning of the frame, so the strongest part of it is used.
+        let segment = &frame[self.strongest_segment(frame)..][..self.segment_length];
+        let segment_energy = segment.iter().map(value).map(|sample| sample * sample).sum::<f64>();
+        let mut searches = [(0.0, (0.0, 0.0)); 8];
+        for (index, search) in searches.iter_mut().enumerate() {
+            *search = self.search(segment, index);
+        }
+        let power = |index: usize| {
+            let (_, (cos, sin)) = searches[index];
+            cos * cos + sin * sin
+        };
+        let strongest = |indices: ::std::ops::Range<usize>| {
+            let first = indices.start;
+            indices.fold(first, |max, index| if power(index) > power(max) { index } else { max })
+        };
+        let (low, high) = (strongest(0..4), strongest(4..8));
+        let (low_freq, high_freq) = (FREQUENCIES[low], FREQUENCIES[high]);
+        let deviations = (searches[low].0, searches[high].0);
+        let actual = (low_freq as f64 * (1.0 + deviations.0),
+                      high_freq as f64 * (1.0 + deviations.1));
+        let fits = fit_tones(segment, self.sample_rate, actual);
+        let measure = |(cos, sin): (f64, f64)| match segment_energy > 0.0 {
+            true => 2.0 * (cos * cos + sin * sin) / (segment.len() as f64 * segment_energy),
+            false => 0.0,
+        };
+        let (low_ratio, high_ratio) = (measure(fits.0), measure(fits.1));
+        let harmonics = harmonics(segment, self.sample_rate, actual, fits);
+
+        // The remaining power is measured directly, as the powers of both tones overlap slightly.
+        let remaining = residuals(segment, self.sample_rate, actual, fits)
+            .map(|sample| sample * sample)
+            .sum::<f64>();
+        let ratio = match segment_energy > 0.0 {
+            true => (1.0 - remaining / segment_energy).clamp(0.0, 1.0),
+            false => 0.0,
+        };
+
+        // The frequency tolerance is left to the acceptance limits, so the tones are required to
+        // dominate the frame at their actual frequencies.
+        let tones = match energy / len >= MIN_POWER && low_ratio + high_ratio >= MIN_TONE_RATIO {
+            true => Signal::from_frequencies((low_freq, high_freq)),
+            false => None,
+        };
+
+        // Speech contains harmonics, while the tones of a signal are pure and in tune.
+        DetectionReport {
+            signal: match harmonics.0 < MAX_HARMONICS.0 && harmonics.1 < MAX_HARMONICS.1 &&
+                          deviations.0.abs() < REJECTED_DEVIATION &&
+                          deviations.1.abs() < REJECTED_DEVIATION {
+                true => tones,
+                false => None,
+            },
+            tones,
+            powers,
+            energy,
+            frequencies: (low_freq, high_freq),
+            ratio,
+            twist: match low_ratio > 0.0 {
+                true => 10.0 * (high_ratio / low_ratio).log10(),
+                false => 0.0,
+            },
+            deviations,
+            harmonics,
+        }
+    }
+
+    /// Returns the start of the segment within a frame, which contains the most energy.
+    fn strongest_segment<S>(&self, frame: &[S]) -> usize
+        where S: Sample + ToSample<f64>
+    {
+        let square = |sample: &S| value(sample) * value(sample);
+        let mut energy = frame[..self.segment_length].iter().map(square).sum::<f64>();
+        let (mut strongest, mut max_energy) = (0, energy);
+        for start in 1..frame.len() - self.segment_length + 1 {
+            energy += square(&frame[start + self.segment_length - 1]) - square(&frame[start - 1]);
+            if energy > max_energy {
+                strongest = start;
+                max_energy = energy;
+            }
+        }
+        strongest
+    }
+
+    /// Searches the strongest tone next to the DTMF frequency with the given index and returns its
+    /// estimated relative deviation together with the fit of the tone at it.
+    fn search<S>(&self, segment: &[S], index: usize) -> (f64, (f64, f64))
+        where S: Sample + ToSample<f64>
+    {
+        let freq = FREQUENCIES[index] as f64;
+        let step = self.sample_rate / segment.len() as f64;
+        let magnitude = |offset: i32| {
+            let mut bin = GoertzelBin::at(freq + offset as f64 * step, self.sample_rate);
+            for (sample, weight) in segment.iter().map(value).zip(self.window.iter()) {
+                bin.add_sample(sample * weight);
+            }
+            bin.calculate().sqrt()
+        };
+
+        // Find the strongest bin within the searched deviation. Then interpolate between it and
+        // its stronger neighbour using the shape of the Hann window.
+        let range = (MAX_DEVIATION * freq / step) as i32;
+        let (peak, center) = (-range..range + 1)
+            .map(|offset| (offset, magnitude(offset)))
+            .fold((0, -1.0), |max, bin| if bin.1 > max.1 { bin } else { max });
+        let (lower, upper) = (magnitude(peak - 1), magnitude(peak + 1));
+        let offset = match (upper > lower, center > 0.0) {
+            (_, false) => 0.0,
+            (true, true) => (2.0 * upper - center) / (center + upper),
+            (false, true) => -(2.0 * lower - center) / (center + lower),
+        };
+        // Tones beyond the searched deviation belong to other frequencies.
+        let deviation = (peak as f64 + offset) * step / freq;
+        match deviation.abs() <= MAX_DEVIATION {
+            true => (deviation, fit(segment, self.sample_rate, freq * (1.0 + deviation))),
+            false => (deviation, (0.0, 0.0)),
+        }
+    }
+}
+
+/// Projects the samples onto a tone and returns the sums of its cosine and its sine part.
+fn fit<S>(samples: &[S], sample_rate: f64, freq: f64) -> (f64, f64)
+    where S: Sample + ToSample<f64>
+{
+    let mut tone = Phasor::new(freq, sample_rate);
+    samples.iter().map(value).fold((0., 0.), |(cos, sin), sample| {
+        let (phase_cos, phase_sin) = tone.advance();
+        (cos + sample * phase_cos, sin + sample * phase_sin)
+    })
+}
+
+/// Fits both tones jointly to the samples, such that their overlap is taken into account, and
+/// returns the sums of their cosine and their sine parts like `fit`.
+fn fit_tones<S>(samples: &[S], sample_rate: f64, tones: (f64, f64)) -> ((f64, f64), (f64, f64))
+    where S: Sample + ToSample<f64>
+{
+    // Set up the normal equations of the least squares fit of both tones.
+    let (mut low, mut high) = (Phasor::new(tones.0, sample_rate),
+                               Phasor::new(tones.1, sample_rate));
+    let mut system = [[0.0; 5]; 4];
+    for sample in samples.iter().map(value) {
+        let ((low_cos, low_sin), (high_cos, high_sin)) = (low.advance(), high.advance());
+        let basis = [low_cos, low_sin, high_cos, high_sin];
+        for (row, &first) in system.iter_mut().zip(basis.iter()) {
+            for (entry, &second) in row.iter_mut().zip(basis.iter()) {
+                *entry += first * second;
+            }
+            row[4] += first * sample;
+        }
+    }
+
+    let amplitudes = solve(system);
+    let scale = samples.len() as f64 / 2.0;
+    ((scale * amplitudes[0], scale * amplitudes[1]),
+     (scale * amplitudes[2], scale * amplitudes[3]))
+}
+
+/// Solves a system of four linear equations given by its augmented matrix.
+fn solve(mut system: [[f64; 5]; 4]) -> [f64; 4] {
+    for column in 0..4 {
+        let pivot = (column..4).fold(column, |max, row| {
+            match system[row][column].abs() > system[max][column].abs() {
+                true => row,
+                false => max,
+            }
+        });
+        system.swap(column, pivot);
+        let row = system[column];
+        if row[column] == 0.0 {
+            continue;
+        }
+        for other in system[column + 1..].iter_mut() {
+            let factor = other[column] / row[column];
+            for (entry, &value) in other.iter_mut().zip(row.iter()) {
+                *entry -= factor * value;
+            }
+        }
+    }
+
+    let mut solution = [0.0; 4];
+    for column in (0..4).rev() {
+        let row = system[column];
+        let known = row[column + 1..4]
+            .iter()
+            .zip(solution[column + 1..].iter())
+            .map(|(factor, value)| factor * value)
+            .sum::<f64>();
+        solution[column] = match row[column] != 0.0 {
+            true => (row[4] - known) / row[column],
+            false => 0.0,
+        };
+    }
+    solution
+}
+
+/// Returns the samples without the fitted tones.
+fn residuals<'a, S>(segment: &'a [S],
+                    sample_rate: f64,
+                    tones: (f64, f64),
+                    fits: ((f64, f64), (f64, f64)))
+                    -> impl Iterator<Item = f64> + 'a
+    where S: Sample + ToSample<f64>
+{
+    let scale = 2.0 / segment.len() as f64;
+    let (mut low, mut high) = (Phasor::new(tones.0, sample_rate),
+                               Phasor::new(tones.1, sample_rate));
+    let tone = move |(cos, sin): (f64, f64), (fit_cos, fit_sin): (f64, f64)| {
+        scale * (fit_cos * cos + fit_sin * sin)
+    };
+    segment.iter()
+        .map(value)
+        .map(move |sample| sample - tone(low.advance(), fits.0) - tone(high.advance(), fits.1))
+}
+
+/// Measures the power of the second harmonics of both tones relative to the tones in dB.
+fn harmonics<S>(segment: &[S],
+                sample_rate: f64,
+                tones: (f64, f64),
+                fits: ((f64, f64), (f64, f64)))
+                -> (f64, f64)
+    where S: Sample + ToSample<f64>
+{
+    // Remove the fitted tones, such that they could not leak into the bins of the harmonics: The
+    // harmonics of the lower tones are close to the higher ones.
+    let mut bins = (GoertzelBin::at(2.0 * tones.0, sample_rate),
+                    GoertzelBin::at(2.0 * tones.1, sample_rate));
+    for residual in residuals(segment, sample_rate, tones, fits) {
+        bins.0.add_sample(residual);
+        bins.1.add_sample(residual);
+    }
+
+    let harmonic = |bin: GoertzelBin, (cos, sin): (f64, f64)| {
+        let power = cos * cos + sin * sin;
+        // Without a tone, there are no harmonics of it.
+        match power > 0.0 {
+            true => 10.0 * (bin.calculate() / power).log10(),
+            false => f64::NEG_INFINITY,
+        }
+    };
+    (harmonic(bins.0, fits.0), harmonic(bins.1, fits.1))
+}
+
+/// Converts a sample of any format into a floating point one.
+fn value<S>(sample: &S) -> f64
+    where S: Sample + ToSample<f64>
+{
+    sample.to_sample()
+}
+
+/// A rotating pointer generating the cosine and the sine of a tone without trigonometric
+/// functions for each sample.
+struct Phasor {
+    current: (f64, f64),
+    step: (f64, f64),
+}
+
+impl Phasor {
+    fn new(freq: f64, sample_rate: f64) -> Phasor {
+        let omega = 2.0 * ::std::f64::consts::PI * freq / sample_rate;
+        Phasor {
+            current: (1.0, 0.0),
+            step: (omega.cos(), omega.sin()),
+        }
+    }
+
+    /// Returns the cosine and the sine of the current phase and advances to the next sample.
+    fn advance(&mut self) -> (f64, f64) {
+        let (cos, sin) = self.current;
+        self.current = (cos * self.step.0 - sin * self.step.1,
+                        sin * self.step.0 + cos * self.step.1);
+        (cos, sin)
+    }
+}
+
+/// An bin for the goertzel algorithm.
+#[derive(Clone, Copy, Debug)]
+pub struct GoertzelBin {
     real: f64,
     coeff: (f64, f64),
     freq: u16,
@@ -64,31 +634,17 @@ impl GoertzelBin {
         GoertzelBin {
             real: 2.0 * (2.0 * ::std::f64::consts::PI * f).cos(),
             coeff: (0., 0.),
-            freq: freq,
+            freq,
         }
     }
 
-    /// Applies the Goertzel algorithm on an slice of frequencies and returns the bins.
-    pub fn apply_goerzel<S, F>(samples: S, sample_rate: f64, freqs: F) -> Vec<GoertzelBin>
-        where S: Iterator<Item = f64> + ExactSizeIterator,
-              F: Iterator<Item = u16>
-    {
-
-        let len = samples.len();
-        let step = sample_rate / len as f64;
-
-        // Create the GoertzelBins from the frequencies
-        // TODO: freq > (len as f64) - 1f64?!
-        let mut bins: Vec<_> = freqs.map(|freq| GoertzelBin::new(freq, step, len)).collect();
-
-        // Fill them with the samples
-        for sample in samples {
-            for bin in bins.iter_mut() {
-                bin.add_sample(sample)
-            }
+    /// Creates a new bin for an arbitrary frequency.
+    fn at(freq: f64, sample_rate: f64) -> GoertzelBin {
+        GoertzelBin {
+            real: 2.0 * (2.0 * ::std::f64::consts::PI * freq / sample_rate).cos(),
+            coeff: (0., 0.),
+            freq: freq as u16,
         }
-
-        bins
     }
 
     /// Adds an sample to the bin.
@@ -106,23 +662,3 @@ impl GoertzelBin {
         self.freq
     }
 }
-
-impl Ord for GoertzelBin {
-    fn cmp(&self, other: &GoertzelBin) -> Ordering {
-        self.calculate().partial_cmp(&other.calculate()).expect("Non NaN")
-    }
-}
-
-impl Eq for GoertzelBin {}
-
-impl PartialOrd for GoertzelBin {
-    fn partial_cmp(&self, other: &GoertzelBin) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl PartialEq for GoertzelBin {
-    fn eq(&self, other: &GoertzelBin) -> bool {
-        self.calculate() == other.calculate()
-    }
-}
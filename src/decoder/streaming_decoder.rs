@@ -0,0 +1,323 @@
+use sample::{Sample, ToSample};
+
+use Signal;
+
+use super::{AcceptanceLimits, DecodeError, Decoder, DetectionReport, DigitEvent};
+
+/// The duration of a single analysed frame in seconds.
+const FRAME_DURATION: f64 = 0.025;
+/// The number of frames which are overlapping at each sample.
+const FRAME_OVERLAP: usize = 2;
+/// The number of consecutive frames which have to contain a signal before it is reported.
+const ONSET_FRAMES: usize = 2;
+/// The number of consecutive frames without the signal which end a key press.
+const OFFSET_FRAMES: usize = 2;
+
+/// A decoder which detects DTMF signals in a continuous stream of samples.
+///
+/// In contrast to `decode_message`, the durations of signals and silence do not need to be known:
+/// The stream is analysed frame by frame and each signal is reported exactly once per key press.
+/// The positions of the reported events are accurate up to a quarter of a frame.
+///
+/// Each key press is checked against the `AcceptanceLimits` of the decoder once it is finished.
+/// The frequencies, the twist, the signal-to-noise ratio and the harmonics are taken from the
+/// frame which contains the signal most clearly.
+#[derive(Clone, Debug)]
+pub struct StreamingDecoder {
+    sample_rate: f64,
+    limits: AcceptanceLimits,
+    decoder: Decoder,
+    frame_length: usize,
+    frame: Vec<f64>,
+    position: usize,
+    report: Option<DetectionReport>,
+    candidate: Option<(Signal, DetectionReport)>,
+    candidate_frames: usize,
+    candidate_start: usize,
+    current: Option<(Signal, DetectionReport)>,
+    current_start: usize,
+    current_end: usize,
+    missing_frames: usize,
+}
+
+impl StreamingDecoder {
+    /// Creates a new decoder for a stream with the given sample rate.
+    /// # Example
+    /// ```
+    /// use dtmf::Signal;
+    /// use dtmf::encoder::SignalEncoder;
+    /// use dtmf::decoder::StreamingDecoder;
+    ///
+    /// let tone = SignalEncoder::new(Signal::B, 8000.).take(800).map(|x| x[0]);
+    /// let silence = ::std::iter::repeat(0.).take(400);
+    ///
+    /// let mut decoder = StreamingDecoder::new(8000.);
+    /// let events = decoder.process(tone.chain(silence)).unwrap();
+    /// assert_eq!(events.len(), 1);
+    /// assert_eq!(events[0].signal(), Signal::B);
+    /// ```
+    pub fn new(sample_rate: f64) -> StreamingDecoder {
+        StreamingDecoder::with_limits(sample_rate, AcceptanceLimits::default())
+    }
+
+    /// Creates a new decoder for a stream with the given sample rate, which only reports signals
+    /// within the given limits.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    /// use dtmf::encoder::MessageEncoder;
+    /// use dtmf::decoder::{AcceptanceLimits, StreamingDecoder};
+    ///
+    /// // The second signal is too short according to Q.24.
+    /// let mut first = Message::new(0.05, 0.05);
+    /// first.enqueue(Signal::digit(4).unwrap());
+    /// let mut second = Message::new(0.015, 0.05);
+    /// second.enqueue(Signal::digit(2).unwrap());
+    ///
+    /// let samples = MessageEncoder::new(&first, 8000.)
+    ///     .chain(MessageEncoder::new(&second, 8000.))
+    ///     .map(|x| x[0])
+    ///     .chain(::std::iter::repeat(0.).take(400));
+    ///
+    /// let mut decoder = StreamingDecoder::with_limits(8000., AcceptanceLimits::q24());
+    /// let events = decoder.process(samples).unwrap();
+    ///
+    /// assert_eq!(events.len(), 1);
+    /// assert_eq!(events[0].signal(), Signal::digit(4).unwrap());
+    /// ```
+    pub fn with_limits(sample_rate: f64, limits: AcceptanceLimits) -> StreamingDecoder {
+        let frame_length = ((sample_rate * FRAME_DURATION) as usize).max(1);
+        StreamingDecoder {
+            sample_rate,
+            limits,
+            decoder: Decoder::new(sample_rate, frame_length).expect("Frames are long enough"),
+            frame_length,
+            frame: Vec::with_capacity(frame_length),
+            position: 0,
+            report: None,
+            candidate: None,
+            candidate_frames: 0,
+            candidate_start: 0,
+            current: None,
+            current_start: 0,
+            current_end: 0,
+            missing_frames: 0,
+        }
+    }
+
+    /// Returns the limits a signal has to satisfy.
+    pub fn limits(&self) -> &AcceptanceLimits {
+        &self.limits
+    }
+
+    /// Adds a single sample to the decoder and returns an event, iff a key press has finished.
+    ///
+    /// Samples which are not finite are rejected without changing the state of the decoder.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    /// use dtmf::encoder::MessageEncoder;
+    /// use dtmf::decoder::StreamingDecoder;
+    ///
+    /// let mut message = Message::new(0.1, 0.05);
+    /// message.enqueue(Signal::C);
+    ///
+    /// // Unsigned 8-bit samples are centered around 128.
+    /// let mut decoder = StreamingDecoder::new(8000.);
+    /// let mut events = Vec::new();
+    /// for sample in MessageEncoder::new(&message, 8000.).map(|x| (128. + 127. * x[0]) as u8) {
+    ///     events.extend(decoder.push(sample).unwrap());
+    /// }
+    /// events.extend(decoder.finish());
+    ///
+    /// assert_eq!(events.len(), 1);
+    /// assert_eq!(events[0].signal(), Signal::C);
+    /// ```
+    pub fn push<S>(&mut self, sample: S) -> Result<Option<DigitEvent>, DecodeError>
+        where S: Sample + ToSample<f64>
+    {
+        let sample = sample.to_sample::<f64>();
+        if !sample.is_finite() {
+            return Err(DecodeError::InvalidSample(self.position));
+        }
+
+        self.frame.push(sample);
+        self.position += 1;
+        if self.frame.len() < self.frame_length {
+            return Ok(None);
+        }
+
+        let report = self.decoder.measure(&self.frame);
+        self.report = Some(report);
+
+        // Keep the overlapping part of the frame for the next one.
+        let hop = (self.frame_length / FRAME_OVERLAP).max(1);
+        self.frame.drain(..hop);
+        Ok(self.update(report.tones().map(|signal| (signal, report))))
+    }
+
+    /// Adds a chunk of samples to the decoder and returns all finished key presses.
+    ///
+    /// The processing stops at the first sample which is not finite.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    /// use dtmf::encoder::MessageEncoder;
+    /// use dtmf::decoder::StreamingDecoder;
+    ///
+    /// let mut message = Message::new(0.1, 0.05);
+    /// message.enqueue(Signal::digit(1).unwrap());
+    /// message.enqueue(Signal::digit(1).unwrap());
+    /// message.enqueue(Signal::Hash);
+    ///
+    /// // Start with some silence, which is not part of the message.
+    /// let samples = ::std::iter::repeat(0.)
+    ///     .take(1234)
+    ///     .chain(MessageEncoder::new(&message, 8000.).map(|x| x[0]))
+    ///     .collect::<Vec<f64>>();
+    ///
+    /// let mut decoder = StreamingDecoder::new(8000.);
+    /// let mut events = Vec::new();
+    /// for chunk in samples.chunks(100) {
+    ///     events.extend(decoder.process(chunk.iter().cloned()).unwrap());
+    /// }
+    /// events.extend(decoder.finish());
+    ///
+    /// let signals = events.iter().map(|event| event.signal()).collect::<Vec<_>>();
+    /// assert_eq!(signals, vec![Signal::digit(1).unwrap(), Signal::digit(1).unwrap(), Signal::Hash]);
+    ///
+    /// // The timing is recovered up to the resolution of the decoder.
+    /// assert!((events[0].start() - 1234. / 8000.).abs() < 0.01);
+    /// assert!((events[0].duration() - 0.1).abs() < 0.01);
+    /// assert!((events[0].gap_to(&events[1]) - 0.05).abs() < 0.01);
+    /// ```
+    pub fn process<S>(&mut self, samples: S) -> Result<Vec<DigitEvent>, DecodeError>
+        where S: IntoIterator,
+              S::Item: Sample + ToSample<f64>
+    {
+        let mut events = Vec::new();
+        for sample in samples {
+            events.extend(self.push(sample)?);
+        }
+        Ok(events)
+    }
+
+    /// Finishes the stream and returns the key press which is still in progress, if any.
+    ///
+    /// Afterwards, the decoder is reset and can be used for another stream.
+    pub fn finish(&mut self) -> Option<DigitEvent> {
+        let event = self.current.and_then(|current| self.event(current));
+        self.reset();
+        event
+    }
+
+    /// Returns the signal which is currently present in the stream, if any.
+    pub fn current(&self) -> Option<Signal> {
+        self.current.map(|(signal, _)| signal)
+    }
+
+    /// Returns the report of the last analysed frame, which is useful to tune the limits.
+    pub fn last_report(&self) -> Option<&DetectionReport> {
+        self.report.as_ref()
+    }
+
+    /// Resets the decoder into its initial state.
+    pub fn reset(&mut self) {
+        self.frame.clear();
+        self.position = 0;
+        self.report = None;
+        self.candidate = None;
+        self.candidate_frames = 0;
+        self.current = None;
+        self.missing_frames = 0;
+    }
+
+    /// Returns the key press in progress as an event, which ends with the last detected frame,
+    /// iff it would be accepted if it was released now.
+    pub(crate) fn current_event(&self) -> Option<DigitEvent> {
+        self.current.and_then(|current| self.event(current))
+    }
+
+    /// Creates the event of a key press of the given signal, which ended with the last detected
+    /// frame.
+    fn span(&self, signal: Signal) -> DigitEvent {
+        // A frame contains the signal, iff about the half of it is covered by the signal.
+        let margin = self.frame_length / 4;
+        DigitEvent::new(signal,
+                        self.current_start + margin,
+                        self.current_end.saturating_sub(margin).max(self.current_start + margin),
+                        self.sample_rate)
+    }
+
+    /// Creates the event of a key press which ended with the last detected frame, iff it is
+    /// within the limits.
+    fn event(&self, (signal, report): (Signal, DetectionReport)) -> Option<DigitEvent> {
+        let event = self.span(signal);
+        match report.signal() == Some(signal) &&
+              self.limits.accepts_duration(event.duration()) &&
+              self.limits.accepts_twist(report.twist()) &&
+              self.limits.accepts_deviation(report.deviations().0) &&
+              self.limits.accepts_deviation(report.deviations().1) &&
+              self.limits.accepts_snr(report.snr()) {
+            true => Some(event),
+            false => None,
+        }
+    }
+
+    /// Updates the state of the key press detection with the result of the frame ending at the
+    /// current position.
+    fn update(&mut self, detected: Option<(Signal, DetectionReport)>) -> Option<DigitEvent> {
+        let frame_start = self.position - self.frame_length;
+        let signal = detected.map(|(signal, _)| signal);
+        let mut event = None;
+
+        if let Some((current, report)) = self.current {
+            if signal == Some(current) {
+                let best = best(report, detected.expect("Detected signal").1);
+                self.current = Some((current, best));
+                self.current_end = self.position;
+                self.missing_frames = 0;
+                return None;
+            }
+
+            self.missing_frames += 1;
+            if self.missing_frames < OFFSET_FRAMES {
+                return None;
+            }
+
+            // The key was released.
+            event = self.event((current, report));
+            self.current = None;
+            self.missing_frames = 0;
+            self.candidate = None;
+        }
+
+        match (self.candidate, detected) {
+            (Some((candidate, report)), Some((signal, other))) if candidate == signal => {
+                self.candidate = Some((candidate, best(report, other)));
+                self.candidate_frames += 1;
+            }
+            _ => {
+                self.candidate = detected;
+                self.candidate_frames = 1;
+                self.candidate_start = frame_start;
+            }
+        }
+
+        if self.candidate.is_some() && self.candidate_frames >= ONSET_FRAMES {
+            self.current = self.candidate.take();
+            self.current_start = self.candidate_start;
+            self.current_end = self.position;
+        }
+
+        event
+    }
+}
+
+/// Returns the report of the frame which contains the signal more clearly.
+fn best(report: DetectionReport, other: DetectionReport) -> DetectionReport {
+    match other.confidence() > report.confidence() {
+        true => other,
+        false => report,
+    }
+}
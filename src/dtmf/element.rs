@@ -0,0 +1,48 @@
+use super::Signal;
+
+/// An entry of a `Message`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Element {
+    /// A signal lasting for the given duration in seconds, which is separated from the next
+    /// signal by the given silence in seconds. Missing durations are taken from the message.
+    Tone {
+        /// The encoded signal.
+        signal: Signal,
+        /// The duration of the signal, if it differs from the one of the message.
+        duration: Option<f64>,
+        /// The duration of the following silence, if it differs from the one of the message.
+        silence: Option<f64>,
+    },
+    /// Silence lasting for the given duration in seconds.
+    Pause(f64),
+    /// Waits for a confirmation, like a dial tone or an user input, before the message continues.
+    Wait,
+    /// A hook flash, which is silent within the audio.
+    Flash,
+}
+
+impl Element {
+    /// Creates a new tone, whose durations are taken from the message.
+    pub fn tone(signal: Signal) -> Element {
+        Element::Tone {
+            signal,
+            duration: None,
+            silence: None,
+        }
+    }
+
+    /// Returns the signal of the element, iff it is a tone.
+    /// # Example
+    /// ```
+    /// use dtmf::{Element, Signal};
+    ///
+    /// assert_eq!(Element::tone(Signal::A).signal(), Some(Signal::A));
+    /// assert_eq!(Element::Pause(2.).signal(), None);
+    /// ```
+    pub fn signal(&self) -> Option<Signal> {
+        match *self {
+            Element::Tone { signal, .. } => Some(signal),
+            _ => None,
+        }
+    }
+}
@@ -1,18 +1,58 @@
-use std::collections::vec_deque::Iter;
+use std::collections::vec_deque::{IntoIter, Iter};
 use std::collections::VecDeque;
+use std::iter::FromIterator;
+use std::ops::{Bound, Index, RangeBounds};
 use std::str::FromStr;
 use std::fmt::{Display, Formatter, Result as FormatResult};
+use std::error::Error;
 
-use super::{Signal, SignalParsingError};
+use super::{Element, Signal};
 
-pub type SignalIterator<'a> = Iter<'a, Signal>;
+/// The duration of a pause within a dial string in seconds.
+const DIAL_PAUSE: f64 = 2.0;
+/// The duration of a hook flash in seconds.
+const FLASH_DURATION: f64 = 0.6;
 
-/// A message of `Signal`s in a queue.
+pub type ElementIterator<'a> = Iter<'a, Element>;
+
+/// An iterator over the signals of a message.
+#[derive(Clone, Debug)]
+pub struct SignalIterator<'a>(ElementIterator<'a>);
+
+impl<'a> Iterator for SignalIterator<'a> {
+    type Item = &'a Signal;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for element in self.0.by_ref() {
+            if let Element::Tone { ref signal, .. } = *element {
+                return Some(signal);
+            }
+        }
+        None
+    }
+}
+
+/// An iterator which moves the signals out of a message.
+#[derive(Clone, Debug)]
+pub struct IntoSignalIterator(IntoIter<Element>);
+
+impl Iterator for IntoSignalIterator {
+    type Item = Signal;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.by_ref().filter_map(|element| element.signal()).next()
+    }
+}
+
+/// A message of `Signal`s and pauses in a queue.
+///
+/// Each signal may have its own duration and its own silence to the next signal. Otherwise, the
+/// durations of the message are used.
 /// # Hint
 /// A `Message` guaranties correctness of its signals.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Message {
-    signals: VecDeque<Signal>,
+    elements: VecDeque<Element>,
     signal_duration: f64,
     silence_duration: f64,
 }
@@ -21,9 +61,9 @@ impl Message {
     /// Creates a new message with given durations.
     pub fn new(signal_duration: f64, silence_duration: f64) -> Message {
         Message {
-            signals: VecDeque::new(),
-            signal_duration: signal_duration,
-            silence_duration: silence_duration,
+            elements: VecDeque::new(),
+            signal_duration,
+            silence_duration,
         }
     }
 
@@ -32,19 +72,11 @@ impl Message {
     /// ```
     /// use dtmf::{Message, Signal};
     ///
-    /// assert!(Message::from_slice(&[Signal::A, Signal::B]).is_some());
-    /// assert!(Message::from_slice(&[Signal::Digit(42)]).is_none());
+    /// let message = Message::from_slice(&[Signal::A, Signal::B]);
+    /// assert_eq!(message.to_string(), "AB");
     /// ```
-    pub fn from_slice(signals: &[Signal]) -> Option<Message> {
-        if signals.iter().all(|signal| signal.frequencies().is_some()) {
-            let mut message = Message::default();
-            for signal in signals {
-                message.enqueue(*signal);
-            }
-            Some(message)
-        } else {
-            None
-        }
+    pub fn from_slice(signals: &[Signal]) -> Message {
+        signals.iter().cloned().collect()
     }
 
     /// Sets the duration of each signal in seconds.
@@ -67,23 +99,62 @@ impl Message {
         self.silence_duration
     }
 
-    /// Adds a signal to the queue, iff it is valid.
+    /// Adds a signal to the queue.
     /// # Example
     /// ```
     /// use dtmf::{Message, Signal};
     ///
     /// let mut message = Message::default();
-    /// assert!(message.enqueue(Signal::A));
-    /// assert!(!message.enqueue(Signal::Digit(42)));
-    /// ```
-    pub fn enqueue(&mut self, signal: Signal) -> bool {
-        match signal.frequencies() {
-            Some(_) => {
-                self.signals.push_back(signal);
-                true
-            }
-            None => false,
-        }
+    /// message.enqueue(Signal::A);
+    /// assert_eq!(message.len(), 1);
+    /// ```
+    pub fn enqueue(&mut self, signal: Signal) {
+        self.enqueue_element(Element::tone(signal))
+    }
+
+    /// Adds a signal with its own durations in seconds to the queue.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    ///
+    /// let mut message = Message::new(0.25, 0.25);
+    /// message.enqueue_with_durations(Signal::digit(0).unwrap(), 0.5, 1.0);
+    /// message.enqueue(Signal::digit(1).unwrap());
+    ///
+    /// assert_eq!(message.duration(), 1.75);
+    /// ```
+    pub fn enqueue_with_durations(&mut self,
+                                  signal: Signal,
+                                  signal_duration: f64,
+                                  silence_duration: f64) {
+        self.enqueue_element(Element::Tone {
+            signal,
+            duration: Some(signal_duration),
+            silence: Some(silence_duration),
+        })
+    }
+
+    /// Adds a pause of the given duration in seconds to the queue.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    ///
+    /// let mut message = Message::new(0.25, 0.5);
+    /// message.enqueue(Signal::digit(9).unwrap());
+    /// message.enqueue_pause(2.0);
+    /// message.enqueue(Signal::digit(5).unwrap());
+    ///
+    /// // Pauses are not signals, but extend the silence between them.
+    /// assert_eq!(message.len(), 2);
+    /// assert_eq!(message.duration(), 3.0);
+    /// ```
+    pub fn enqueue_pause(&mut self, duration: f64) {
+        self.elements.push_back(Element::Pause(duration));
+    }
+
+    /// Adds an element, like a signal or a pause, to the queue.
+    pub fn enqueue_element(&mut self, element: Element) {
+        self.elements.push_back(element);
     }
 
     /// Returns the first signal from the queue.
@@ -99,7 +170,17 @@ impl Message {
     /// assert_eq!(message.dequeue(), None);
     /// ```
     pub fn dequeue(&mut self) -> Option<Signal> {
-        self.signals.pop_front()
+        while let Some(element) = self.elements.pop_front() {
+            if let Some(signal) = element.signal() {
+                return Some(signal);
+            }
+        }
+        None
+    }
+
+    /// Returns the first element, a signal or a pause, from the queue.
+    pub fn dequeue_element(&mut self) -> Option<Element> {
+        self.elements.pop_front()
     }
 
     /// Returns the number of signals in the queue.
@@ -114,46 +195,426 @@ impl Message {
     /// assert_eq!(message.len(), 2);
     /// ```
     pub fn len(&self) -> usize {
-        self.signals.len()
+        self.iter().count()
+    }
+
+    /// Returns true, iff the queue contains no signals.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
     }
 
     /// Returns an inmutable iterator over the signals.
-    pub fn iter(&self) -> SignalIterator {
-        self.signals.iter()
+    pub fn iter(&self) -> SignalIterator<'_> {
+        SignalIterator(self.elements.iter())
+    }
+
+    /// Returns an inmutable iterator over all elements, like signals and pauses.
+    pub fn elements(&self) -> ElementIterator<'_> {
+        self.elements.iter()
+    }
+
+    /// Returns the durations in seconds of the given element and of the silence following it, if
+    /// there is another signal afterwards.
+    pub fn durations(&self, element: &Element) -> (f64, f64) {
+        match *element {
+            Element::Tone { duration, silence, .. } => {
+                (duration.unwrap_or(self.signal_duration),
+                 silence.unwrap_or(self.silence_duration))
+            }
+            Element::Pause(duration) => (duration, 0.0),
+            Element::Wait => (0.0, 0.0),
+            Element::Flash => (FLASH_DURATION, 0.0),
+        }
+    }
+
+    /// Returns the duration of the whole message in seconds.
+    ///
+    /// Each signal is followed by its silence, unless it is the last one.
+    pub fn duration(&self) -> f64 {
+        let last = self.elements.iter().rposition(|element| element.signal().is_some());
+        self.elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| {
+                let (duration, silence) = self.durations(element);
+                match last.is_some_and(|last| index < last) {
+                    true => duration + silence,
+                    false => duration,
+                }
+            })
+            .sum()
+    }
+
+    /// Splits the message at each wait into parts, which could be encoded one after another once
+    /// the wait is confirmed.
+    /// # Example
+    /// ```
+    /// use dtmf::Message;
+    ///
+    /// let message = "9,,5551234;1234".parse::<Message>().unwrap();
+    /// let parts = message.split_at_waits();
+    ///
+    /// assert_eq!(parts.len(), 2);
+    /// assert_eq!(parts[0].to_string(), "9,,5551234");
+    /// assert_eq!(parts[1].to_string(), "1234");
+    /// ```
+    pub fn split_at_waits(&self) -> Vec<Message> {
+        let part = Message::new(self.signal_duration, self.silence_duration);
+        let mut parts = vec![part.clone()];
+        for element in self.elements() {
+            match *element {
+                Element::Wait => parts.push(part.clone()),
+                element => parts.last_mut().expect("Current part").elements.push_back(element),
+            }
+        }
+        parts
+    }
+
+    /// Returns the signal at the given index, if there is one.
+    pub fn get(&self, index: usize) -> Option<&Signal> {
+        self.iter().nth(index)
+    }
+
+    /// Adds a signal to the front of the queue.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    ///
+    /// let mut message = "23".parse::<Message>().unwrap();
+    /// message.push_front(Signal::digit(1).unwrap());
+    /// assert_eq!(message.to_string(), "123");
+    /// ```
+    pub fn push_front(&mut self, signal: Signal) {
+        self.elements.push_front(Element::tone(signal));
+    }
+
+    /// Removes all elements from the queue, while keeping the durations.
+    pub fn clear(&mut self) {
+        self.elements.clear();
+    }
+
+    /// Keeps the first `len` signals and removes everything after them.
+    /// # Example
+    /// ```
+    /// use dtmf::Message;
+    ///
+    /// let mut message = "12,34".parse::<Message>().unwrap();
+    /// message.truncate(2);
+    /// assert_eq!(message.to_string(), "12");
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        match len {
+            0 => self.elements.clear(),
+            _ => {
+                if let Some(position) = self.position(len - 1) {
+                    self.elements.truncate(position + 1);
+                }
+            }
+        }
+    }
+
+    /// Returns a message with the signals in the given range and the elements between them.
+    /// # Example
+    /// ```
+    /// use dtmf::Message;
+    ///
+    /// let message = "12,34#".parse::<Message>().unwrap();
+    ///
+    /// assert_eq!(message.slice(1..4).to_string(), "2,34");
+    /// assert_eq!(message.slice(..2).to_string(), "12");
+    /// assert_eq!(message.slice(5..).to_string(), "");
+    /// ```
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Message {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => usize::MAX,
+        };
+
+        let mut message = Message::new(self.signal_duration, self.silence_duration);
+        if start < end {
+            if let Some(first) = self.position(start) {
+                let last = self.position(end - 1)
+                    .or_else(|| self.elements.iter().rposition(|e| e.signal().is_some()))
+                    .expect("Last signal");
+                message.elements.extend(self.elements.range(first..last + 1));
+            }
+        }
+        message
+    }
+
+    /// Moves all elements of the other message to the end of this one.
+    ///
+    /// Signals of the other message keep their durations, even if the messages differ in them.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    ///
+    /// let mut message = Message::new(0.25, 0.25);
+    /// message.enqueue(Signal::A);
+    /// let mut other = Message::new(0.5, 0.25);
+    /// other.enqueue(Signal::B);
+    ///
+    /// message.append(&mut other);
+    /// assert_eq!(message.to_string(), "AB");
+    /// assert_eq!(message.duration(), 1.0);
+    /// assert!(other.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Message) {
+        let (signal_duration, silence_duration) = (other.signal_duration, other.silence_duration);
+        let explicit = |own: f64, duration: Option<f64>, default: f64| match own == default {
+            true => duration,
+            false => duration.or(Some(default)),
+        };
+        for element in other.elements.drain(..) {
+            self.elements.push_back(match element {
+                Element::Tone { signal, duration, silence } => {
+                    Element::Tone {
+                        signal,
+                        duration: explicit(self.signal_duration, duration, signal_duration),
+                        silence: explicit(self.silence_duration, silence, silence_duration),
+                    }
+                }
+                element => element,
+            });
+        }
+    }
+
+    /// Returns true, iff both messages contain the same signals regardless of their durations
+    /// and of the pauses between them.
+    /// # Example
+    /// ```
+    /// use dtmf::Message;
+    ///
+    /// let mut first = "1,2".parse::<Message>().unwrap();
+    /// first.set_signal_duration(0.1);
+    /// let second = "12".parse::<Message>().unwrap();
+    ///
+    /// assert_ne!(first, second);
+    /// assert!(first.eq_ignoring_timing(&second));
+    /// ```
+    pub fn eq_ignoring_timing(&self, other: &Message) -> bool {
+        self.iter().eq(other.iter())
+    }
+
+    /// Returns the position of the element containing the signal at the given index.
+    fn position(&self, index: usize) -> Option<usize> {
+        self.elements
+            .iter()
+            .enumerate()
+            .filter(|&(_, element)| element.signal().is_some())
+            .map(|(position, _)| position)
+            .nth(index)
     }
 }
 
 impl Default for Message {
     fn default() -> Message {
         Message {
-            signals: VecDeque::new(),
+            elements: VecDeque::new(),
             signal_duration: 0.7,
             silence_duration: 0.3,
         }
     }
 }
 
+/// Returns the signal at the given index.
+/// # Panics
+/// Panics, iff there is no signal at the index.
+/// # Example
+/// ```
+/// use dtmf::{Message, Signal};
+///
+/// let message = "1,#".parse::<Message>().unwrap();
+/// assert_eq!(message[1], Signal::Hash);
+/// ```
+impl Index<usize> for Message {
+    type Output = Signal;
+
+    fn index(&self, index: usize) -> &Signal {
+        self.get(index).expect("Signal index out of bounds")
+    }
+}
+
+/// Moves the signals out of the message, skipping all other elements.
+impl IntoIterator for Message {
+    type Item = Signal;
+    type IntoIter = IntoSignalIterator;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoSignalIterator(self.elements.into_iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a Message {
+    type Item = &'a Signal;
+    type IntoIter = SignalIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Collects signals into a message with the default durations.
+/// # Example
+/// ```
+/// use dtmf::{Message, Signal};
+///
+/// let message = Signal::iter().filter(|signal| signal.column() == 0).collect::<Message>();
+/// assert_eq!(message.to_string(), "147*");
+///
+/// let signals = message.into_iter().collect::<Vec<_>>();
+/// assert_eq!(signals.len(), 4);
+/// ```
+impl FromIterator<Signal> for Message {
+    fn from_iter<T: IntoIterator<Item = Signal>>(iter: T) -> Self {
+        let mut message = Message::default();
+        message.extend(iter);
+        message
+    }
+}
+
+impl Extend<Signal> for Message {
+    fn extend<T: IntoIterator<Item = Signal>>(&mut self, iter: T) {
+        for signal in iter {
+            self.enqueue(signal);
+        }
+    }
+}
+
+/// Collects borrowed signals into a message with the default durations.
+impl<'a> FromIterator<&'a Signal> for Message {
+    fn from_iter<T: IntoIterator<Item = &'a Signal>>(iter: T) -> Self {
+        iter.into_iter().cloned().collect()
+    }
+}
+
+impl<'a> Extend<&'a Signal> for Message {
+    fn extend<T: IntoIterator<Item = &'a Signal>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+/// Parses a dial string.
+///
+/// Besides the signals, `,` inserts a pause of two seconds, `p`, `w` and `;` wait for a
+/// confirmation and `!` inserts a hook flash. Whitespace and `-` are ignored.
+/// # Example
+/// ```
+/// use dtmf::{Element, Message, Signal};
+///
+/// let message = "9,,555-1234;w1234!p0".parse::<Message>().unwrap();
+/// let elements = message.elements().cloned().collect::<Vec<_>>();
+///
+/// assert_eq!(message.len(), 13);
+/// assert_eq!(elements[0], Element::tone(Signal::digit(9).unwrap()));
+/// assert_eq!(elements[1], Element::Pause(2.));
+/// assert_eq!(elements[10], Element::Wait);
+/// assert_eq!(elements[16], Element::Flash);
+/// assert_eq!(elements[17], Element::Wait);
+/// ```
 impl FromStr for Message {
-    type Err = char;
+    type Err = MessageParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut result = Message::default();
-        for char in s.chars() {
-            match Signal::from_char(char) {
-                Ok(signal) => result.enqueue(signal),
-                Err(SignalParsingError::UnknownSignal(unknown)) => return Err(unknown),
-                _ => panic!("Char separation failed"),
-            };
+        for (position, char) in s.chars().enumerate() {
+            match char {
+                ',' => result.enqueue_pause(DIAL_PAUSE),
+                'p' | 'P' | 'w' | 'W' | ';' => result.enqueue_element(Element::Wait),
+                '!' => result.enqueue_element(Element::Flash),
+                '-' => {}
+                separator if separator.is_whitespace() => {}
+                _ => {
+                    match Signal::from_char(char) {
+                        Ok(signal) => result.enqueue(signal),
+                        Err(_) => return Err(MessageParseError::new(char, position)),
+                    };
+                }
+            }
         }
         Ok(result)
     }
 }
 
+/// Writes the message as a dial string.
+///
+/// A dial string does not contain any durations: Each pause is written as the number of `,`
+/// which lasts about as long, but at least one.
+/// # Example
+/// ```
+/// use dtmf::{Message, Signal};
+///
+/// let mut message = Message::default();
+/// message.enqueue(Signal::digit(1).unwrap());
+/// message.enqueue_pause(0.5);
+/// message.enqueue(Signal::digit(2).unwrap());
+/// message.enqueue_pause(4.0);
+/// message.enqueue(Signal::digit(3).unwrap());
+/// assert_eq!(message.to_string(), "1,2,,3");
+/// ```
 impl Display for Message {
     fn fmt(&self, f: &mut Formatter) -> FormatResult {
-        for signal in self.iter() {
-            write!(f, "{}", signal)?;
+        for element in self.elements() {
+            match *element {
+                Element::Tone { signal, .. } => write!(f, "{}", signal)?,
+                Element::Pause(duration) => {
+                    let pauses = ((duration / DIAL_PAUSE).round() as usize).max(1);
+                    write!(f, "{}", ",".repeat(pauses))?
+                }
+                Element::Wait => write!(f, "w")?,
+                Element::Flash => write!(f, "!")?,
+            }
         }
         Ok(())
     }
 }
+
+/// An error occurring while parsing a message, which points to the invalid character.
+/// # Example
+/// ```
+/// use dtmf::Message;
+///
+/// let error = "123-45x6".parse::<Message>().unwrap_err();
+/// assert_eq!(error.character(), 'x');
+/// assert_eq!(error.position(), 6);
+/// assert_eq!(error.to_string(), "invalid character 'x' at position 6");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MessageParseError {
+    character: char,
+    position: usize,
+}
+
+impl MessageParseError {
+    /// Creates a new error for the given character at the given position.
+    pub fn new(character: char, position: usize) -> MessageParseError {
+        MessageParseError {
+            character,
+            position,
+        }
+    }
+
+    /// The invalid character.
+    pub fn character(&self) -> char {
+        self.character
+    }
+
+    /// The position of the invalid character counted in characters, not in bytes.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Display for MessageParseError {
+    fn fmt(&self, f: &mut Formatter) -> FormatResult {
+        write!(f, "invalid character '{}' at position {}", self.character, self.position)
+    }
+}
+
+impl Error for MessageParseError {}
@@ -1,5 +1,10 @@
 pub mod signal;
 pub mod message;
+pub mod element;
+#[cfg(feature = "serde")]
+mod serialization;
 
-pub use self::signal::{Signal, SignalParsingError};
-pub use self::message::{Message, SignalIterator};
+pub use self::signal::{Digit, Signal, SignalParsingError};
+pub use self::message::{Message, MessageParseError, SignalIterator, IntoSignalIterator,
+                        ElementIterator};
+pub use self::element::Element;
@@ -0,0 +1,126 @@
+//! Support for serde, which is enabled by the `serde` feature.
+//!
+//! A `Signal` is serialized as its char, while a `Message` is serialized as the list of its
+//! elements together with its durations. Tones and pauses keep their own durations.
+//! # Example
+//! ```
+//! extern crate dtmf;
+//! extern crate serde_json;
+//!
+//! use dtmf::{Element, Message, Signal};
+//!
+//! # fn main() {
+//! assert_eq!(serde_json::to_string(&Signal::Hash).unwrap(), "\"#\"");
+//! assert_eq!(serde_json::from_str::<Signal>("\"7\"").ok(), Signal::digit(7));
+//! assert!(serde_json::from_str::<Signal>("\"x\"").is_err());
+//!
+//! let mut message = Message::new(0.1, 0.05);
+//! message.enqueue_with_durations(Signal::A, 0.3, 0.2);
+//! message.enqueue_pause(0.5);
+//! message.enqueue(Signal::B);
+//! message.enqueue_element(Element::Wait);
+//! message.enqueue_element(Element::Flash);
+//!
+//! let json = serde_json::to_string(&message).unwrap();
+//! assert_eq!(json,
+//!            concat!(r#"{"elements":[{"type":"tone","signal":"A","duration":0.3,"silence":0.2},"#,
+//!                    r#"{"type":"pause","duration":0.5},{"type":"tone","signal":"B"},"#,
+//!                    r#"{"type":"wait"},{"type":"flash"}],"#,
+//!                    r#""signal_duration":0.1,"silence_duration":0.05}"#));
+//!
+//! let deserialized = serde_json::from_str::<Message>(&json).unwrap();
+//! assert_eq!(deserialized, message);
+//! assert_eq!(deserialized.duration(), message.duration());
+//! # }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{Error, Unexpected};
+
+use super::{Element, Message, Signal};
+
+impl Serialize for Signal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(self.to_char())
+    }
+}
+
+impl<'de> Deserialize<'de> for Signal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = char::deserialize(deserializer)?;
+        Signal::from_char(input)
+            .map_err(|_| D::Error::invalid_value(Unexpected::Char(input), &"a DTMF signal"))
+    }
+}
+
+/// The serialized form of a message.
+#[derive(Serialize, Deserialize)]
+struct MessageData {
+    elements: Vec<ElementData>,
+    signal_duration: f64,
+    silence_duration: f64,
+}
+
+/// The serialized form of an element of a message.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ElementData {
+    Tone {
+        signal: Signal,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duration: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        silence: Option<f64>,
+    },
+    Pause { duration: f64 },
+    Wait,
+    Flash,
+}
+
+/// Serializes the message as its elements and its durations.
+impl Serialize for Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let elements = self.elements()
+            .map(|element| match *element {
+                Element::Tone { signal, duration, silence } => {
+                    ElementData::Tone {
+                        signal,
+                        duration,
+                        silence,
+                    }
+                }
+                Element::Pause(duration) => ElementData::Pause { duration },
+                Element::Wait => ElementData::Wait,
+                Element::Flash => ElementData::Flash,
+            })
+            .collect();
+        let data = MessageData {
+            elements,
+            signal_duration: self.signal_duration(),
+            silence_duration: self.silence_duration(),
+        };
+        data.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = MessageData::deserialize(deserializer)?;
+        let mut message = Message::new(data.signal_duration, data.silence_duration);
+        for element in data.elements {
+            message.enqueue_element(match element {
+                ElementData::Tone { signal, duration, silence } => {
+                    Element::Tone {
+                        signal,
+                        duration,
+                        silence,
+                    }
+                }
+                ElementData::Pause { duration } => Element::Pause(duration),
+                ElementData::Wait => Element::Wait,
+                ElementData::Flash => Element::Flash,
+            });
+        }
+        Ok(message)
+    }
+}
@@ -1,12 +1,78 @@
 use std::str::FromStr;
 use std::slice::Iter;
+use std::convert::TryFrom;
 use std::fmt::{Display, Formatter, Result as FormatResult};
 
+/// The lower frequencies of the rows of the keypad.
+const ROW_FREQUENCIES: [u16; 4] = [697, 770, 852, 941];
+/// The upper frequencies of the columns of the keypad.
+const COLUMN_FREQUENCIES: [u16; 4] = [1209, 1336, 1477, 1633];
+
+/// The signals in the layout of the keypad.
+const KEYPAD: [[Signal; 4]; 4] = [[Signal::Digit(Digit(1)),
+                                   Signal::Digit(Digit(2)),
+                                   Signal::Digit(Digit(3)),
+                                   Signal::A],
+                                  [Signal::Digit(Digit(4)),
+                                   Signal::Digit(Digit(5)),
+                                   Signal::Digit(Digit(6)),
+                                   Signal::B],
+                                  [Signal::Digit(Digit(7)),
+                                   Signal::Digit(Digit(8)),
+                                   Signal::Digit(Digit(9)),
+                                   Signal::C],
+                                  [Signal::Asterisk,
+                                   Signal::Digit(Digit(0)),
+                                   Signal::Hash,
+                                   Signal::D]];
+
+/// A decimal digit from 0 - 9.
+/// # Example
+/// ```
+/// use std::convert::TryFrom;
+/// use dtmf::{Digit, SignalParsingError};
+///
+/// assert_eq!(Digit::new(7).map(|digit| digit.value()), Some(7));
+/// assert_eq!(Digit::new(42), None);
+/// assert_eq!(Digit::try_from(42), Err(SignalParsingError::InvalidDigit(42)));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Digit(u8);
+
+impl Digit {
+    /// Creates a digit, iff the value is below 10.
+    pub fn new(value: u8) -> Option<Digit> {
+        match value < 10 {
+            true => Some(Digit(value)),
+            false => None,
+        }
+    }
+
+    /// Returns the value of the digit.
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for Digit {
+    type Error = SignalParsingError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Digit::new(value).ok_or(SignalParsingError::InvalidDigit(value))
+    }
+}
+
+impl From<Digit> for u8 {
+    fn from(digit: Digit) -> u8 {
+        digit.0
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 /// A valid signal for DTMF.
 pub enum Signal {
     /// A digit from 0 - 9.
-    Digit(u8),
+    Digit(Digit),
     /// Encodes the signal "A".
     A,
     /// Encodes the signal "B".
@@ -22,13 +88,25 @@ pub enum Signal {
 }
 
 impl Signal {
+    /// Generates the signal of a digit, iff the value is below 10.
+    /// # Example
+    /// ```
+    /// use dtmf::{Digit, Signal};
+    ///
+    /// assert_eq!(Signal::digit(7), Digit::new(7).map(Signal::Digit));
+    /// assert_eq!(Signal::digit(42), None);
+    /// ```
+    pub fn digit(value: u8) -> Option<Signal> {
+        Digit::new(value).map(Signal::Digit)
+    }
+
     /// Generates a signal from a char.
     /// # Example
     /// ```
     /// use dtmf::{Signal, SignalParsingError};
     ///
     /// assert_eq!(Signal::from_char('A'), Ok(Signal::A));
-    /// assert_eq!(Signal::from_char('7'), Ok(Signal::Digit(7)));
+    /// assert_eq!(Signal::from_char('7').ok(), Signal::digit(7));
     /// assert_eq!(Signal::from_char('💣'), Err(SignalParsingError::UnknownSignal('💣')));
     /// ```
     pub fn from_char(input: char) -> Result<Self, SignalParsingError> {
@@ -39,10 +117,72 @@ impl Signal {
             'B' | 'b' => Ok(Signal::B),
             'C' | 'c' => Ok(Signal::C),
             'D' | 'd' => Ok(Signal::D),
-            number @ '0'...'9' => {
-                Ok(Signal::Digit(number.to_digit(10).expect("Invalid number") as u8))
-            }
-            unknown @ _ => Err(SignalParsingError::UnknownSignal(unknown)),
+            number @ '0'..='9' => Ok(Signal::Digit(Digit(number as u8 - b'0'))),
+            unknown => Err(SignalParsingError::UnknownSignal(unknown)),
+        }
+    }
+
+    /// Generates a signal from an ASCII character like `b'5'` or `b'#'`.
+    /// # Example
+    /// ```
+    /// use dtmf::{Signal, SignalParsingError};
+    ///
+    /// assert_eq!(Signal::from_ascii(b'#'), Ok(Signal::Hash));
+    /// assert_eq!(Signal::from_ascii(b'5'), Signal::from_char('5'));
+    /// assert_eq!(Signal::from_ascii(b'x'), Err(SignalParsingError::UnknownSignal('x')));
+    /// ```
+    pub fn from_ascii(input: u8) -> Result<Self, SignalParsingError> {
+        Signal::from_char(input as char)
+    }
+
+    /// Returns the char of the signal.
+    pub fn to_char(&self) -> char {
+        match *self {
+            Signal::Asterisk => '*',
+            Signal::Hash => '#',
+            Signal::A => 'A',
+            Signal::B => 'B',
+            Signal::C => 'C',
+            Signal::D => 'D',
+            Signal::Digit(digit) => (b'0' + digit.0) as char,
+        }
+    }
+
+    /// Generates a signal from its row and its column on the keypad, both starting at zero.
+    /// # Example
+    /// ```
+    /// use dtmf::Signal;
+    ///
+    /// for &signal in Signal::iter() {
+    ///     assert_eq!(Signal::from_position(signal.row(), signal.column()), Some(signal));
+    /// }
+    /// assert_eq!(Signal::from_position(3, 0), Some(Signal::Asterisk));
+    /// assert_eq!(Signal::from_position(4, 0), None);
+    /// ```
+    pub fn from_position(row: usize, column: usize) -> Option<Signal> {
+        KEYPAD.get(row).and_then(|signals| signals.get(column)).cloned()
+    }
+
+    /// Returns the row of the signal on the keypad, which determines its lower frequency.
+    pub fn row(&self) -> usize {
+        match *self {
+            Signal::Digit(Digit(0)) => 3,
+            Signal::Digit(Digit(number)) => (number as usize - 1) / 3,
+            Signal::A => 0,
+            Signal::B => 1,
+            Signal::C => 2,
+            Signal::D | Signal::Asterisk | Signal::Hash => 3,
+        }
+    }
+
+    /// Returns the column of the signal on the keypad, which determines its upper frequency.
+    pub fn column(&self) -> usize {
+        match *self {
+            Signal::Digit(Digit(0)) => 1,
+            Signal::Digit(Digit(number)) => (number as usize - 1) % 3,
+            Signal::Asterisk => 0,
+            Signal::Hash => 2,
+            Signal::A | Signal::B | Signal::C | Signal::D => 3,
         }
     }
 
@@ -52,30 +192,15 @@ impl Signal {
     /// use dtmf::Signal;
     ///
     /// for &signal in Signal::iter() {
-    ///     let frequencies = signal.frequencies().expect("Valid signals");
-    ///     assert_eq!(Some(signal), Signal::from_frequencies(frequencies));
+    ///     assert_eq!(Some(signal), Signal::from_frequencies(signal.frequencies()));
     /// }
+    /// assert_eq!(Signal::from_frequencies((697, 1000)), None);
     /// ```
     pub fn from_frequencies(frequencies: (u16, u16)) -> Option<Signal> {
-        match (frequencies.0, frequencies.1) {
-            (941, 1336) => Some(Signal::Digit(0)),
-            (697, 1209) => Some(Signal::Digit(1)),
-            (697, 1336) => Some(Signal::Digit(2)),
-            (697, 1477) => Some(Signal::Digit(3)),
-            (770, 1209) => Some(Signal::Digit(4)),
-            (770, 1336) => Some(Signal::Digit(5)),
-            (770, 1477) => Some(Signal::Digit(6)),
-            (852, 1209) => Some(Signal::Digit(7)),
-            (852, 1336) => Some(Signal::Digit(8)),
-            (852, 1477) => Some(Signal::Digit(9)),
-            // Valid letters
-            (697, 1633) => Some(Signal::A),
-            (770, 1633) => Some(Signal::B),
-            (852, 1633) => Some(Signal::C),
-            (941, 1633) => Some(Signal::D),
-            // Other symbols
-            (941, 1209) => Some(Signal::Asterisk),
-            (941, 1477) => Some(Signal::Hash),
+        let row = ROW_FREQUENCIES.iter().position(|&frequency| frequency == frequencies.0);
+        let column = COLUMN_FREQUENCIES.iter().position(|&frequency| frequency == frequencies.1);
+        match (row, column) {
+            (Some(row), Some(column)) => Signal::from_position(row, column),
             _ => None,
         }
     }
@@ -85,52 +210,27 @@ impl Signal {
     /// ```
     /// use dtmf::Signal;
     ///
-    /// let signal = Signal::A;
-    /// let (low, high) = signal.frequencies().expect("Valid signal");
+    /// let (low, high) = Signal::A.frequencies();
     ///
     /// assert_eq!(low, 697);
     /// assert_eq!(high, 1633);
     /// ```
-    pub fn frequencies(&self) -> Option<(u16, u16)> {
-
-        // Just a bunch of constants...
-        match *self {
-            // Valid digits
-            Signal::Digit(0) => Some((941, 1336)),
-            Signal::Digit(1) => Some((697, 1209)),
-            Signal::Digit(2) => Some((697, 1336)),
-            Signal::Digit(3) => Some((697, 1477)),
-            Signal::Digit(4) => Some((770, 1209)),
-            Signal::Digit(5) => Some((770, 1336)),
-            Signal::Digit(6) => Some((770, 1477)),
-            Signal::Digit(7) => Some((852, 1209)),
-            Signal::Digit(8) => Some((852, 1336)),
-            Signal::Digit(9) => Some((852, 1477)),
-            // Valid letters
-            Signal::A => Some((697, 1633)),
-            Signal::B => Some((770, 1633)),
-            Signal::C => Some((852, 1633)),
-            Signal::D => Some((941, 1633)),
-            // Other symbols
-            Signal::Asterisk => Some((941, 1209)),
-            Signal::Hash => Some((941, 1477)),
-            // Invalid digit
-            _ => None,
-        }
+    pub fn frequencies(&self) -> (u16, u16) {
+        (ROW_FREQUENCIES[self.row()], COLUMN_FREQUENCIES[self.column()])
     }
 
     /// Returns an iterator over all valid signals.
     pub fn iter() -> Iter<'static, Signal> {
-        static VALID_SIGNALS: [Signal; 16] = [Signal::Digit(0),
-                                              Signal::Digit(1),
-                                              Signal::Digit(2),
-                                              Signal::Digit(3),
-                                              Signal::Digit(4),
-                                              Signal::Digit(5),
-                                              Signal::Digit(6),
-                                              Signal::Digit(7),
-                                              Signal::Digit(8),
-                                              Signal::Digit(9),
+        static VALID_SIGNALS: [Signal; 16] = [Signal::Digit(Digit(0)),
+                                              Signal::Digit(Digit(1)),
+                                              Signal::Digit(Digit(2)),
+                                              Signal::Digit(Digit(3)),
+                                              Signal::Digit(Digit(4)),
+                                              Signal::Digit(Digit(5)),
+                                              Signal::Digit(Digit(6)),
+                                              Signal::Digit(Digit(7)),
+                                              Signal::Digit(Digit(8)),
+                                              Signal::Digit(Digit(9)),
                                               Signal::A,
                                               Signal::B,
                                               Signal::C,
@@ -138,7 +238,7 @@ impl Signal {
                                               Signal::Asterisk,
                                               Signal::Hash];
 
-        VALID_SIGNALS.into_iter()
+        VALID_SIGNALS.iter()
     }
 }
 
@@ -151,6 +251,8 @@ pub enum SignalParsingError {
     TooLong,
     /// An unknown signal appears.
     UnknownSignal(char),
+    /// A digit larger than 9 appears.
+    InvalidDigit(u8),
 }
 
 impl FromStr for Signal {
@@ -167,20 +269,50 @@ impl FromStr for Signal {
     }
 }
 
+/// Generates a signal from a char like `Signal::from_char`.
+impl TryFrom<char> for Signal {
+    type Error = SignalParsingError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Signal::from_char(value)
+    }
+}
+
+/// Generates the signal of a digit from its value like `Signal::digit`.
+/// # Example
+/// ```
+/// use std::convert::TryFrom;
+/// use dtmf::{Digit, Signal, SignalParsingError};
+///
+/// assert_eq!(Signal::try_from(5u8), Ok(Signal::digit(5).unwrap()));
+/// assert_eq!(Signal::try_from(5u8), Digit::try_from(5u8).map(Signal::Digit));
+/// assert_eq!(Signal::try_from(b'5'), Err(SignalParsingError::InvalidDigit(b'5')));
+/// ```
+impl TryFrom<u8> for Signal {
+    type Error = SignalParsingError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Digit::try_from(value).map(Signal::Digit)
+    }
+}
+
+/// Returns the char of the signal.
+/// # Example
+/// ```
+/// use dtmf::Signal;
+///
+/// for &signal in Signal::iter() {
+///     assert_eq!(Signal::from_char(char::from(signal)), Ok(signal));
+/// }
+/// ```
+impl From<Signal> for char {
+    fn from(signal: Signal) -> char {
+        signal.to_char()
+    }
+}
+
 impl Display for Signal {
     fn fmt(&self, f: &mut Formatter) -> FormatResult {
-        write!(f,
-               "{}",
-               match *self {
-                   Signal::Asterisk => '*',
-                   Signal::Hash => '#',
-                   Signal::A => 'A',
-                   Signal::B => 'B',
-                   Signal::C => 'C',
-                   Signal::D => 'D',
-                   Signal::Digit(number) => {
-                       ::std::char::from_digit(number as u32, 10).expect("Valid digit")
-                   }
-               })
+        write!(f, "{}", self.to_char())
     }
 }
@@ -0,0 +1,206 @@
+use super::Envelope;
+
+/// The level in dBFS of a sine with 0 dBm0 according to ITU-T G.711 µ-law, which differs from the
+/// one of A-law by 0.03 dB.
+const DBM0_LEVEL: f64 = -3.17;
+
+/// The levels of the tones generated by an encoder.
+///
+/// Levels are given in dB relative to a sine with full amplitude (dBFS) or in dBm0 as used by
+/// telephone networks. The twist is the level of the higher tone relative to the lower one.
+/// # Example
+/// ```
+/// use dtmf::encoder::EncoderConfig;
+///
+/// let config = EncoderConfig::from_dbm0(-9., -7.).expect("Valid levels");
+/// assert!((config.twist() - 2.).abs() < 1e-9);
+/// assert!((config.high_level() + 10.17).abs() < 1e-9);
+///
+/// // The tones must not exceed the full scale together.
+/// assert!(EncoderConfig::from_dbfs(-3., -3.).is_none());
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EncoderConfig {
+    low_amplitude: f64,
+    high_amplitude: f64,
+    envelope: Envelope,
+    rise_time: f64,
+    fall_time: f64,
+    continuous_phase: bool,
+}
+
+impl EncoderConfig {
+    /// Creates a new configuration with the levels of the lower and the higher tone in dBFS, iff
+    /// both tones do not exceed the full scale together.
+    pub fn from_dbfs(low_level: f64, high_level: f64) -> Option<EncoderConfig> {
+        EncoderConfig::from_amplitudes(amplitude(low_level), amplitude(high_level))
+    }
+
+    /// Creates a new configuration with the levels of the lower and the higher tone in dBm0, iff
+    /// both tones do not exceed the full scale together.
+    pub fn from_dbm0(low_level: f64, high_level: f64) -> Option<EncoderConfig> {
+        EncoderConfig::from_dbfs(low_level + DBM0_LEVEL, high_level + DBM0_LEVEL)
+    }
+
+    /// Creates a new configuration with the linear amplitudes of the lower and the higher tone,
+    /// iff both tones do not exceed the full scale together.
+    pub fn from_amplitudes(low_amplitude: f64, high_amplitude: f64) -> Option<EncoderConfig> {
+        match low_amplitude >= 0.0 && high_amplitude >= 0.0 &&
+              low_amplitude + high_amplitude <= 1.0 {
+            true => {
+                Some(EncoderConfig {
+                    low_amplitude,
+                    high_amplitude,
+                    ..EncoderConfig::default()
+                })
+            }
+            false => None,
+        }
+    }
+
+    /// Changes the level of the lower tone such that the higher one is louder by the given twist
+    /// in dB, iff both tones do not exceed the full scale afterwards.
+    /// # Example
+    /// ```
+    /// use dtmf::encoder::EncoderConfig;
+    ///
+    /// let config = EncoderConfig::from_dbfs(-10., -10.).unwrap().with_twist(3.).unwrap();
+    /// assert!((config.low_level() + 13.).abs() < 1e-9);
+    /// assert!((config.high_level() + 10.).abs() < 1e-9);
+    /// ```
+    pub fn with_twist(&self, twist: f64) -> Option<EncoderConfig> {
+        EncoderConfig::from_dbfs(self.high_level() - twist, self.high_level()).map(|config| {
+            EncoderConfig {
+                envelope: self.envelope,
+                rise_time: self.rise_time,
+                fall_time: self.fall_time,
+                continuous_phase: self.continuous_phase,
+                ..config
+            }
+        })
+    }
+
+    /// Shapes each tone by the given envelope, which rises and falls within the given durations in
+    /// seconds.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    /// use dtmf::encoder::{EncoderConfig, Envelope, MessageEncoder};
+    ///
+    /// let mut message = Message::new(0.1, 0.05);
+    /// message.enqueue(Signal::digit(8).unwrap());
+    ///
+    /// let config = EncoderConfig::default().with_envelope(Envelope::RaisedCosine, 0.005, 0.005);
+    /// let samples = MessageEncoder::with_config(&message, 8000., config).collect::<Vec<_>>();
+    ///
+    /// // The tone starts and ends quietly.
+    /// assert!(samples[..4].iter().all(|x| x[0].abs() < 0.05));
+    /// assert!(samples[796..].iter().all(|x| x[0].abs() < 0.05));
+    /// ```
+    pub fn with_envelope(&self,
+                         envelope: Envelope,
+                         rise_time: f64,
+                         fall_time: f64)
+                         -> EncoderConfig {
+        EncoderConfig {
+            envelope,
+            rise_time: rise_time.max(0.0),
+            fall_time: fall_time.max(0.0),
+            ..*self
+        }
+    }
+
+    /// Lets each tone continue with the phases the previous tone ended with, instead of starting
+    /// both oscillators at zero.
+    ///
+    /// Without silence between the signals, this avoids discontinuities at each transition.
+    /// # Example
+    /// ```
+    /// use dtmf::Message;
+    /// use dtmf::encoder::{EncoderConfig, MessageEncoder};
+    ///
+    /// let mut message = "1D".parse::<Message>().unwrap();
+    /// message.set_signal_duration(0.1);
+    /// message.set_silence_duration(0.);
+    ///
+    /// // The largest difference between two samples of a sine is limited by its frequency.
+    /// let max_step = |config: EncoderConfig| {
+    ///     let samples = MessageEncoder::with_config(&message, 48000., config).collect::<Vec<_>>();
+    ///     samples.windows(2).map(|x| (x[1][0] - x[0][0]).abs()).fold(0., f64::max)
+    /// };
+    /// let (low, high) = EncoderConfig::default().amplitudes();
+    /// let limit = 2. * ::std::f64::consts::PI * (low * 941. + high * 1633.) / 48000.;
+    ///
+    /// assert!(max_step(EncoderConfig::default()) > limit);
+    /// assert!(max_step(EncoderConfig::default().with_continuous_phase(true)) <= limit);
+    /// ```
+    pub fn with_continuous_phase(&self, continuous_phase: bool) -> EncoderConfig {
+        EncoderConfig {
+            continuous_phase,
+            ..*self
+        }
+    }
+
+    /// The level of the lower tone in dBFS.
+    pub fn low_level(&self) -> f64 {
+        level(self.low_amplitude)
+    }
+
+    /// The level of the higher tone in dBFS.
+    pub fn high_level(&self) -> f64 {
+        level(self.high_amplitude)
+    }
+
+    /// The level of the higher tone relative to the lower one in dB.
+    pub fn twist(&self) -> f64 {
+        self.high_level() - self.low_level()
+    }
+
+    /// The linear amplitudes of the lower and the higher tone.
+    pub fn amplitudes(&self) -> (f64, f64) {
+        (self.low_amplitude, self.high_amplitude)
+    }
+
+    /// The envelope each tone is shaped by.
+    pub fn envelope(&self) -> Envelope {
+        self.envelope
+    }
+
+    /// The duration in seconds until a tone reaches its full amplitude.
+    pub fn rise_time(&self) -> f64 {
+        self.rise_time
+    }
+
+    /// The duration in seconds a tone needs to decay completely.
+    pub fn fall_time(&self) -> f64 {
+        self.fall_time
+    }
+
+    /// Whether each tone continues with the phases of the previous one.
+    pub fn continuous_phase(&self) -> bool {
+        self.continuous_phase
+    }
+}
+
+impl Default for EncoderConfig {
+    fn default() -> EncoderConfig {
+        EncoderConfig {
+            low_amplitude: 0.4,
+            high_amplitude: 0.5,
+            envelope: Envelope::default(),
+            rise_time: 0.0,
+            fall_time: 0.0,
+            continuous_phase: false,
+        }
+    }
+}
+
+/// Converts a level in dBFS into a linear amplitude.
+pub(crate) fn amplitude(level: f64) -> f64 {
+    10f64.powf(level / 20.0)
+}
+
+/// Converts a linear amplitude into a level in dBFS.
+pub(crate) fn level(amplitude: f64) -> f64 {
+    20.0 * amplitude.log10()
+}
@@ -0,0 +1,82 @@
+use std::f64::consts::PI;
+
+/// The shape of the amplitude of a tone, which avoids clicks at its start and its end.
+/// # Example
+/// ```
+/// use dtmf::{Message, Signal};
+/// use dtmf::encoder::{EncoderConfig, Envelope, MessageEncoder};
+/// use dtmf::decoder::StreamingDecoder;
+///
+/// let mut message = Message::new(0.06, 0.04);
+/// message.enqueue(Signal::digit(4).unwrap());
+/// message.enqueue(Signal::Hash);
+///
+/// for &envelope in &[Envelope::Linear, Envelope::RaisedCosine, Envelope::Hann] {
+///     let config = EncoderConfig::default().with_envelope(envelope, 0.005, 0.005);
+///     let samples = MessageEncoder::with_config(&message, 8000., config).map(|x| x[0]);
+///
+///     let mut decoder = StreamingDecoder::new(8000.);
+///     let mut events = decoder.process(samples).unwrap();
+///     events.extend(decoder.finish());
+///     assert_eq!(events.len(), 2);
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Envelope {
+    /// Starts and stops the tone abruptly.
+    #[default]
+    Rectangular,
+    /// Ramps the amplitude linearly within the rise and the fall time.
+    Linear,
+    /// Ramps the amplitude along half a period of a cosine within the rise and the fall time.
+    RaisedCosine,
+    /// Shapes the whole tone by a Hann window, regardless of the rise and the fall time.
+    Hann,
+}
+
+impl Envelope {
+    /// Returns the gain of the sample at the given position within a tone of the given length,
+    /// which rises and falls within the given numbers of samples.
+    ///
+    /// If the tone is too short, the rise and the fall are shortened proportionally. Positions
+    /// outside of the tone have no gain.
+    /// # Example
+    /// ```
+    /// use dtmf::encoder::Envelope;
+    ///
+    /// assert_eq!(Envelope::Linear.gain(0, 100, 10, 10), 0.05);
+    /// assert_eq!(Envelope::Linear.gain(50, 100, 10, 10), 1.);
+    /// assert_eq!(Envelope::Linear.gain(99, 100, 10, 10), 0.05);
+    /// assert_eq!(Envelope::Rectangular.gain(0, 100, 10, 10), 1.);
+    ///
+    /// assert_eq!(Envelope::Linear.gain(100, 100, 10, 10), 0.);
+    /// assert_eq!(Envelope::Linear.gain(101, 100, 10, 10), 0.);
+    /// assert_eq!(Envelope::Hann.gain(0, 0, 10, 10), 0.);
+    /// ```
+    pub fn gain(&self, position: usize, length: usize, rise: usize, fall: usize) -> f64 {
+        if position >= length {
+            return 0.0;
+        }
+        let (rise, fall) = match rise + fall > length {
+            true => {
+                let rise = rise * length / (rise + fall);
+                (rise, length - rise)
+            }
+            false => (rise, fall),
+        };
+        let ramp = |progress: f64| match *self {
+            Envelope::Linear => progress,
+            _ => 0.5 - 0.5 * (PI * progress).cos(),
+        };
+
+        match *self {
+            Envelope::Rectangular => 1.0,
+            Envelope::Hann => ramp(2.0 * (position as f64 + 0.5) / length as f64),
+            _ if position < rise => ramp((position as f64 + 0.5) / rise as f64),
+            _ if length - position <= fall => {
+                ramp(((length - position) as f64 - 0.5) / fall as f64)
+            }
+            _ => 1.0,
+        }
+    }
+}
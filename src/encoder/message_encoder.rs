@@ -1,17 +1,15 @@
-use sample::Signal;
-use sample::signal::Delay;
-
-use std::iter::Take;
+use sample::{Sample, FromSample};
 
 use Message;
-use super::SignalEncoder;
+use super::{EncoderConfig, Envelope, SignalEncoder, Samples};
 
 /// An encoder which encodes a DTMF message.
 #[derive(Clone)]
 pub struct MessageEncoder {
-    signals: Vec<Delay<Take<SignalEncoder>>>,
+    parts: Vec<Part>,
     current_index: usize,
     size: usize,
+    continuous_phase: bool,
 }
 
 impl MessageEncoder {
@@ -31,55 +29,145 @@ impl MessageEncoder {
     /// let sample_num = encoder.len();
     /// assert_eq!(encoder.count(), sample_num);
     /// ```
+    /// The timing of each signal and pauses are honored:
+    /// ```
+    /// use dtmf::{Message, Signal};
+    /// use dtmf::encoder::MessageEncoder;
+    ///
+    /// let mut message = Message::new(0.1, 0.1);
+    /// message.enqueue_with_durations(Signal::digit(9).unwrap(), 0.2, 0.05);
+    /// message.enqueue_pause(1.0);
+    /// message.enqueue(Signal::digit(1).unwrap());
+    ///
+    /// let mut encoder = MessageEncoder::new(&message, 8000.);
+    /// assert_eq!(encoder.len(), 1600 + 400 + 8000 + 800);
+    ///
+    /// // The second signal starts after the silence of the first one and the pause.
+    /// let samples = encoder.by_ref().take(10000).collect::<Vec<_>>();
+    /// assert!(samples[1600..].iter().all(|x| x[0] == 0.));
+    /// assert_eq!(encoder.len(), 800);
+    /// ```
     pub fn new(message: &Message, sample_rate: f64) -> MessageEncoder {
-        let signal_length = (message.signal_duration() * sample_rate) as usize;
-        let silence_length = (message.silence_duration() * sample_rate) as usize;
-
-        let mut signals = Vec::new();
-        let mut signal_iterator = message.iter();
-
-        // Add the first signal without delay, the others with it.
-        let size = match signal_iterator.next() {
-            Some(signal) => {
-                signals.push(SignalEncoder::new(*signal, sample_rate)
-                    .expect("Valid signal")
-                    .take(signal_length)
-                    .delay(0));
-
-                for signal in signal_iterator {
-                    signals.push(SignalEncoder::new(*signal, sample_rate)
-                        .expect("Valid signal")
-                        .take(signal_length)
-                        .delay(silence_length));
-                }
+        MessageEncoder::with_config(message, sample_rate, EncoderConfig::default())
+    }
 
-                (message.len() - 1) * (signal_length + silence_length) + signal_length
+    /// Creates a new encoder given a message, a sample rate and the levels of the tones.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    /// use dtmf::encoder::{EncoderConfig, MessageEncoder};
+    ///
+    /// let mut message = Message::default();
+    /// message.enqueue(Signal::digit(1).unwrap());
+    ///
+    /// // A quiet encoder never exceeds the sum of the amplitudes of both tones.
+    /// let config = EncoderConfig::from_dbm0(-9., -7.).unwrap();
+    /// let (low, high) = config.amplitudes();
+    /// assert!(MessageEncoder::with_config(&message, 8000., config).all(|x| x[0].abs() <= low + high));
+    /// ```
+    pub fn with_config(message: &Message,
+                       sample_rate: f64,
+                       config: EncoderConfig)
+                       -> MessageEncoder {
+        let samples = |duration: f64| (duration * sample_rate) as usize;
+        let tone = |signal, length| {
+            Tone {
+                encoder: SignalEncoder::with_config(signal, sample_rate, config),
+                envelope: config.envelope(),
+                position: 0,
+                length,
+                rise: samples(config.rise_time()),
+                fall: samples(config.fall_time()),
             }
-            None => 0,
         };
 
+        // The silence after a signal is only required, if another signal follows.
+        let (mut parts, mut silence, mut pause) = (Vec::new(), 0, 0);
+        for element in message.elements() {
+            let (duration, following) = message.durations(element);
+            match element.signal() {
+                Some(signal) => {
+                    parts.push(Part {
+                        silence: silence + pause,
+                        tone: Some(tone(signal, samples(duration))),
+                    });
+                    silence = samples(following);
+                    pause = 0;
+                }
+                None => pause += samples(duration),
+            }
+        }
+        if pause > 0 {
+            parts.push(Part {
+                silence: pause,
+                tone: None,
+            });
+        }
+
         MessageEncoder {
-            signals: signals,
+            size: parts.iter().map(Part::len).sum(),
+            parts,
             current_index: 0,
-            size: size,
+            continuous_phase: config.continuous_phase(),
         }
     }
+
+    /// Returns an iterator over the plain samples in the given format.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    /// use dtmf::encoder::MessageEncoder;
+    /// use dtmf::decoder::decode_message;
+    ///
+    /// let mut message = Message::default();
+    /// message.enqueue(Signal::digit(0).unwrap());
+    /// message.enqueue(Signal::Hash);
+    ///
+    /// let samples = MessageEncoder::new(&message, 8000.).samples::<i16>().collect::<Vec<i16>>();
+    /// assert_eq!(samples.len(), MessageEncoder::new(&message, 8000.).len());
+    ///
+    /// let mut target_message = Message::default();
+    /// decode_message(samples, &mut target_message, 8000.).unwrap();
+    /// assert_eq!(message, target_message);
+    /// ```
+    pub fn samples<S>(self) -> Samples<MessageEncoder, S>
+        where S: Sample + FromSample<f64>
+    {
+        Samples::new(self)
+    }
+}
+
+impl MessageEncoder {
+    /// Returns true, iff the next sample belongs to a tone.
+    pub(crate) fn in_tone(&self) -> bool {
+        self.parts[self.current_index.min(self.parts.len())..]
+            .iter()
+            .find(|part| part.len() > 0)
+            .is_some_and(|part| part.silence == 0 && part.tone.is_some())
+    }
 }
 
 impl Iterator for MessageEncoder {
     type Item = [f64; 1];
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.current_index < self.signals.len() {
-            true => {
-                self.size = self.size.saturating_sub(1);
-                self.signals[self.current_index].next().or_else(|| {
-                    self.current_index += 1;
-                    self.next()
-                })
+        while let Some(part) = self.parts.get_mut(self.current_index) {
+            if let Some(frame) = part.next() {
+                self.size -= 1;
+                return Some(frame);
+            }
+
+            // The next tone continues where the finished one stopped.
+            let phases = part.tone.as_ref().map(|tone| tone.encoder.phases());
+            self.current_index += 1;
+            if let (true, Some(phases)) = (self.continuous_phase, phases) {
+                if let Some(&mut Part { tone: Some(ref mut tone), .. }) =
+                    self.parts.get_mut(self.current_index) {
+                    tone.encoder.set_phases(phases);
+                }
             }
-            false => None,
         }
+        None
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -92,3 +180,56 @@ impl ExactSizeIterator for MessageEncoder {
         self.size
     }
 }
+
+/// A part of a message consisting of silence followed by a tone, if any.
+#[derive(Clone)]
+struct Part {
+    silence: usize,
+    tone: Option<Tone>,
+}
+
+impl Part {
+    /// The remaining number of samples.
+    fn len(&self) -> usize {
+        self.silence + self.tone.as_ref().map_or(0, |tone| tone.length - tone.position)
+    }
+}
+
+impl Iterator for Part {
+    type Item = [f64; 1];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.silence {
+            0 => self.tone.as_mut().and_then(Iterator::next),
+            _ => {
+                self.silence -= 1;
+                Some([0.0])
+            }
+        }
+    }
+}
+
+/// A single tone of a message shaped by an envelope.
+#[derive(Clone)]
+struct Tone {
+    encoder: SignalEncoder,
+    envelope: Envelope,
+    position: usize,
+    length: usize,
+    rise: usize,
+    fall: usize,
+}
+
+impl Iterator for Tone {
+    type Item = [f64; 1];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.length {
+            return None;
+        }
+
+        let gain = self.envelope.gain(self.position, self.length, self.rise, self.fall);
+        self.position += 1;
+        self.encoder.next().map(|frame| [frame[0] * gain])
+    }
+}
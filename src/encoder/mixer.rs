@@ -0,0 +1,157 @@
+use sample::{Sample, FromSample, ToSample};
+
+use super::{MessageEncoder, Samples};
+use super::encoder_config::{amplitude, level};
+
+/// An encoder which overlays messages onto existing audio, like a recorded or a live voice stream.
+///
+/// The mixed stream is as long as the input. Messages which are still playing at its end are cut
+/// off and the result is limited to the full scale.
+/// # Example
+/// ```
+/// use std::f64::consts::PI;
+/// use dtmf::{Message, Signal};
+/// use dtmf::encoder::{MessageEncoder, Mixer};
+/// use dtmf::decoder::StreamingDecoder;
+///
+/// let voice = (0..16000).map(|i| 0.3 * (2. * PI * 440. * i as f64 / 8000.).sin());
+/// let (one, two) = (Signal::digit(1).unwrap(), Signal::digit(2).unwrap());
+/// let mut message = Message::new(0.1, 0.1);
+/// message.extend(vec![one, two]);
+///
+/// let mut mixer = Mixer::new(voice, 8000.);
+/// mixer.set_level(-6.);
+/// mixer.set_ducking(Some(-20.));
+/// mixer.add(0.5, MessageEncoder::new(&message, 8000.));
+/// mixer.add(1.5, MessageEncoder::new(&message, 8000.));
+///
+/// let mut decoder = StreamingDecoder::new(8000.);
+/// let events = decoder.process(mixer.map(|x| x[0])).unwrap();
+///
+/// let signals = events.iter().map(|event| event.signal()).collect::<Vec<_>>();
+/// assert_eq!(signals, vec![one, two, one, two]);
+/// assert!((events[0].start() - 0.5).abs() < 0.01);
+/// assert!((events[2].start() - 1.5).abs() < 0.01);
+/// ```
+#[derive(Clone)]
+pub struct Mixer<I> {
+    input: I,
+    sample_rate: f64,
+    gain: f64,
+    ducking: Option<f64>,
+    messages: Vec<(usize, MessageEncoder)>,
+    position: usize,
+}
+
+impl<I> Mixer<I>
+    where I: Iterator,
+          I::Item: Sample + ToSample<f64>
+{
+    /// Creates a new mixer for the given input with the given sample rate.
+    pub fn new(input: I, sample_rate: f64) -> Mixer<I> {
+        Mixer {
+            input,
+            sample_rate,
+            gain: 1.0,
+            ducking: None,
+            messages: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Overlays the samples of an encoder starting at the given offset in seconds, which is
+    /// counted from the start of the input.
+    ///
+    /// Messages which start before the current position are played from the current position.
+    pub fn add(&mut self, offset: f64, encoder: MessageEncoder) {
+        let offset = (offset.max(0.0) * self.sample_rate) as usize;
+        self.messages.push((offset, encoder));
+    }
+
+    /// Sets the level in dB of the tones relative to the input, which keeps its level.
+    pub fn set_level(&mut self, level: f64) {
+        self.gain = amplitude(level);
+    }
+
+    /// Sets the level in dB the input is changed by during tones, if any.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    /// use dtmf::encoder::{MessageEncoder, Mixer};
+    ///
+    /// let mut message = Message::new(0.25, 0.25);
+    /// message.enqueue(Signal::A);
+    /// message.enqueue(Signal::B);
+    ///
+    /// // Keep the tones inaudible to see the input alone.
+    /// let mut mixer = Mixer::new(::std::iter::repeat(0.5).take(8000), 8000.);
+    /// mixer.set_level(-200.);
+    /// mixer.set_ducking(Some(-20.));
+    /// mixer.add(0.25, MessageEncoder::new(&message, 8000.));
+    ///
+    /// let samples = mixer.map(|x| x[0]).collect::<Vec<_>>();
+    /// assert!(samples[..2000].iter().all(|&x| x == 0.5));
+    /// assert!(samples[2000..4000].iter().all(|&x| (x - 0.05).abs() < 1e-9));
+    /// assert!(samples[4000..6000].iter().all(|&x| x == 0.5));
+    /// assert!(samples[6000..].iter().all(|&x| (x - 0.05).abs() < 1e-9));
+    /// ```
+    pub fn set_ducking(&mut self, ducking: Option<f64>) {
+        self.ducking = ducking.map(amplitude);
+    }
+
+    /// The level in dB of the tones relative to the input.
+    pub fn level(&self) -> f64 {
+        level(self.gain)
+    }
+
+    /// The level in dB the input is changed by during tones, if any.
+    pub fn ducking(&self) -> Option<f64> {
+        self.ducking.map(level)
+    }
+
+    /// Returns an iterator over the plain samples in the given format.
+    pub fn samples<S>(self) -> Samples<Mixer<I>, S>
+        where S: Sample + FromSample<f64>
+    {
+        Samples::new(self)
+    }
+}
+
+impl<I> Iterator for Mixer<I>
+    where I: Iterator,
+          I::Item: Sample + ToSample<f64>
+{
+    type Item = [f64; 1];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let input = self.input.next()?.to_sample::<f64>();
+
+        // Sum all messages, which have already started.
+        let position = self.position;
+        let (mut tones, mut active) = (0.0, false);
+        for &mut (offset, ref mut encoder) in self.messages.iter_mut() {
+            if offset <= position {
+                active |= encoder.in_tone();
+                tones += encoder.next().map_or(0.0, |frame| frame[0]);
+            }
+        }
+        self.messages.retain(|&(offset, ref encoder)| offset > position || encoder.len() > 0);
+        self.position += 1;
+
+        let input = match (active, self.ducking) {
+            (true, Some(ducking)) => input * ducking,
+            _ => input,
+        };
+        Some([(input + tones * self.gain).clamp(-1.0, 1.0)])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Mixer<I>
+    where I: ExactSizeIterator,
+          I::Item: Sample + ToSample<f64>
+{
+}
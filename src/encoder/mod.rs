@@ -1,5 +1,13 @@
 mod signal_encoder;
 pub mod message_encoder;
+mod samples;
+mod encoder_config;
+mod envelope;
+mod mixer;
 
 pub use self::signal_encoder::SignalEncoder;
 pub use self::message_encoder::MessageEncoder;
+pub use self::samples::Samples;
+pub use self::encoder_config::EncoderConfig;
+pub use self::envelope::Envelope;
+pub use self::mixer::Mixer;
@@ -0,0 +1,44 @@
+use sample::{Sample, FromSample};
+
+use std::marker::PhantomData;
+
+/// An iterator over the plain samples of an encoder in a specific format.
+#[derive(Clone)]
+pub struct Samples<I, S> {
+    frames: I,
+    format: PhantomData<S>,
+}
+
+impl<I, S> Samples<I, S> {
+    /// Creates a new iterator over the samples of the given frames.
+    pub fn new(frames: I) -> Samples<I, S> {
+        Samples {
+            frames,
+            format: PhantomData,
+        }
+    }
+}
+
+impl<I, S> Iterator for Samples<I, S>
+    where I: Iterator<Item = [f64; 1]>,
+          S: Sample + FromSample<f64>
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.frames.next().map(|frame| S::from_sample(frame[0]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.frames.size_hint()
+    }
+}
+
+impl<I, S> ExactSizeIterator for Samples<I, S>
+    where I: ExactSizeIterator<Item = [f64; 1]>,
+          S: Sample + FromSample<f64>
+{
+    fn len(&self) -> usize {
+        self.frames.len()
+    }
+}
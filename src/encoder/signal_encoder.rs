@@ -1,11 +1,16 @@
-use sample::Signal;
-use sample::signal::{rate, Sine, AddAmp, ConstHz, ScaleAmp};
+use std::f64::consts::PI;
+
+use sample::{Sample, FromSample};
 
 use Signal as DtmfSignal;
+use super::{EncoderConfig, Samples};
 
 /// An encoder which encodes a specific DTMF signal.
 #[derive(Clone)]
-pub struct SignalEncoder(AddAmp<ScaleAmp<Sine<ConstHz>>, ScaleAmp<Sine<ConstHz>>>);
+pub struct SignalEncoder {
+    low: Oscillator,
+    high: Oscillator,
+}
 
 impl SignalEncoder {
     /// Creates a new encoder given an specific DTMF signal and a sample rate.
@@ -14,15 +19,77 @@ impl SignalEncoder {
     /// use dtmf::encoder::SignalEncoder;
     /// use dtmf::Signal;
     ///
-    /// assert!(SignalEncoder::new(Signal::Hash, 44_100.0).is_some(), "Encoder was none.");
-    /// assert!(SignalEncoder::new(Signal::Digit(66), 44_100.0).is_none(), "Invalid encoder");
+    /// let samples = SignalEncoder::new(Signal::Hash, 44_100.0).take(100).collect::<Vec<_>>();
+    /// assert_eq!(samples.len(), 100);
+    /// ```
+    pub fn new(signal: DtmfSignal, sample_rate: f64) -> SignalEncoder {
+        SignalEncoder::with_config(signal, sample_rate, EncoderConfig::default())
+    }
+
+    /// Creates a new encoder given an specific DTMF signal, a sample rate and the levels of the
+    /// tones.
+    /// # Example
+    /// ```
+    /// use dtmf::encoder::{EncoderConfig, SignalEncoder};
+    /// use dtmf::decoder::analyze_signal;
+    /// use dtmf::Signal;
+    ///
+    /// let config = EncoderConfig::from_dbfs(-12., -8.).unwrap();
+    /// let encoder = SignalEncoder::with_config(Signal::digit(9).unwrap(), 8000., config);
+    ///
+    /// let report = analyze_signal(encoder.take(400).map(|x| x[0]), 8000.).unwrap();
+    /// assert!((report.twist() - 4.).abs() < 0.5);
+    /// ```
+    pub fn with_config(signal: DtmfSignal,
+                       sample_rate: f64,
+                       config: EncoderConfig)
+                       -> SignalEncoder {
+        let (low_amplitude, high_amplitude) = config.amplitudes();
+        let (f1, f2) = signal.frequencies();
+        SignalEncoder {
+            low: Oscillator::new(f1 as f64 / sample_rate, low_amplitude),
+            high: Oscillator::new(f2 as f64 / sample_rate, high_amplitude),
+        }
+    }
+
+    /// Returns the phases of the next sample of the lower and the higher tone in cycles.
+    pub fn phases(&self) -> (f64, f64) {
+        (self.low.phase, self.high.phase)
+    }
+
+    /// Sets the phases of the next sample of the lower and the higher tone in cycles, which allows
+    /// to continue a previous tone without discontinuities.
+    /// # Example
+    /// ```
+    /// use dtmf::encoder::SignalEncoder;
+    /// use dtmf::Signal;
+    ///
+    /// let mut first = SignalEncoder::new(Signal::A, 8000.);
+    /// let mut second = first.clone();
+    /// first.nth(99);
+    ///
+    /// second.set_phases(first.phases());
+    /// assert_eq!(first.next(), second.next());
+    /// ```
+    pub fn set_phases(&mut self, phases: (f64, f64)) {
+        self.low.phase = phases.0.rem_euclid(1.0);
+        self.high.phase = phases.1.rem_euclid(1.0);
+    }
+
+    /// Returns an iterator over the plain samples in the given format.
+    /// # Example
+    /// ```
+    /// use dtmf::encoder::SignalEncoder;
+    /// use dtmf::Signal;
+    ///
+    /// // Unsigned 8-bit samples are centered around 128.
+    /// let mut samples = SignalEncoder::new(Signal::digit(3).unwrap(), 8000.).samples::<u8>();
+    /// assert_eq!(samples.next(), Some(128));
     /// ```
-    pub fn new(signal: DtmfSignal, sample_rate: f64) -> Option<SignalEncoder> {
-        signal.frequencies().map(|(f1, f2)| {
-            let sine1 = rate(sample_rate).const_hz(f1 as f64).sine().scale_amp(0.4);
-            let sine2 = rate(sample_rate).const_hz(f2 as f64).sine().scale_amp(0.5);
-            SignalEncoder(sine1.add_amp(sine2))
-        })
+    pub fn samples<S>(self) -> Samples<SignalEncoder, S>
+        where S: Sample + FromSample<f64>
+    {
+        Samples::new(self)
     }
 }
 
@@ -30,6 +97,31 @@ impl Iterator for SignalEncoder {
     type Item = [f64; 1];
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next()
+        Some([self.low.advance() + self.high.advance()])
+    }
+}
+
+/// A sine oscillator, whose phase is given in cycles.
+#[derive(Clone, Copy)]
+struct Oscillator {
+    phase: f64,
+    step: f64,
+    amplitude: f64,
+}
+
+impl Oscillator {
+    fn new(step: f64, amplitude: f64) -> Oscillator {
+        Oscillator {
+            phase: 0.0,
+            step,
+            amplitude,
+        }
+    }
+
+    /// Returns the current value and steps the phase forward.
+    fn advance(&mut self) -> f64 {
+        let value = (2.0 * PI * self.phase).sin() * self.amplitude;
+        self.phase = (self.phase + self.step) % 1.0;
+        value
     }
 }
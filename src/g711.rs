@@ -0,0 +1,138 @@
+//! Companding of 16-bit samples according to ITU-T G.711, which is used by most telephone
+//! networks at a sample rate of 8 kHz.
+//! # Example
+//! ```
+//! use dtmf::{Message, Signal};
+//! use dtmf::encoder::MessageEncoder;
+//! use dtmf::decoder::decode_message;
+//! use dtmf::g711::Law;
+//!
+//! let mut message = Message::new(0.1, 0.1);
+//! message.enqueue(Signal::digit(5).unwrap());
+//! message.enqueue(Signal::Hash);
+//!
+//! for &law in &[Law::MuLaw, Law::ALaw] {
+//!     let bytes = MessageEncoder::new(&message, 8000.)
+//!         .samples::<i16>()
+//!         .map(|sample| law.encode(sample))
+//!         .collect::<Vec<u8>>();
+//!
+//!     let mut target_message = Message::new(0.1, 0.1);
+//!     let samples = bytes.iter().map(|&byte| law.decode(byte)).collect::<Vec<i16>>();
+//!     decode_message(samples, &mut target_message, 8000.).unwrap();
+//!     assert_eq!(message, target_message);
+//! }
+//! ```
+
+/// The bias added to the magnitude of µ-law samples.
+const MU_LAW_BIAS: i32 = 0x84;
+/// The largest magnitude of a µ-law sample with 14 bits before the bias is added.
+const MU_LAW_CLIP: i32 = 8159;
+
+/// A companding law of G.711.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Law {
+    /// The µ-law used in North America and Japan.
+    MuLaw,
+    /// The A-law used in Europe and most other countries.
+    ALaw,
+}
+
+impl Law {
+    /// Compresses a linear sample into a byte.
+    /// # Example
+    /// ```
+    /// use dtmf::g711::Law;
+    ///
+    /// assert_eq!(Law::MuLaw.encode(0), 0xFF);
+    /// assert_eq!(Law::ALaw.encode(0), 0xD5);
+    /// assert_eq!(Law::MuLaw.encode(i16::max_value()), 0x80);
+    /// assert_eq!(Law::ALaw.encode(i16::min_value()), 0x2A);
+    /// ```
+    pub fn encode(&self, sample: i16) -> u8 {
+        match *self {
+            Law::MuLaw => encode_mu_law(sample),
+            Law::ALaw => encode_a_law(sample),
+        }
+    }
+
+    /// Expands a byte into a linear sample.
+    /// # Example
+    /// ```
+    /// use dtmf::g711::Law;
+    ///
+    /// for &law in &[Law::MuLaw, Law::ALaw] {
+    ///     // Expanding and compressing again is lossless, but µ-law knows a negative zero.
+    ///     for byte in (0..=255u8).filter(|&byte| (law, byte) != (Law::MuLaw, 0x7F)) {
+    ///         assert_eq!(law.encode(law.decode(byte)), byte);
+    ///     }
+    ///     // The quantization error grows with the magnitude of the sample.
+    ///     for &sample in &[-30000i16, -1000, -10, 10, 1000, 30000] {
+    ///         let error = (law.decode(law.encode(sample)) - sample).abs();
+    ///         assert!(error <= (sample.abs() / 32).max(16));
+    ///     }
+    /// }
+    /// ```
+    pub fn decode(&self, value: u8) -> i16 {
+        match *self {
+            Law::MuLaw => decode_mu_law(value),
+            Law::ALaw => decode_a_law(value),
+        }
+    }
+}
+
+/// Returns the segment of a magnitude given the largest magnitude of the first segment. Each
+/// segment covers twice the range of the previous one.
+fn segment(magnitude: i32, first_end: i32) -> i32 {
+    (0..8).find(|&segment| magnitude < (first_end + 1) << segment).unwrap_or(8)
+}
+
+fn encode_mu_law(sample: i16) -> u8 {
+    let sample = (sample as i32) >> 2;
+    let (magnitude, mask) = match sample < 0 {
+        true => (-sample, 0x7F),
+        false => (sample, 0xFF),
+    };
+    let magnitude = magnitude.min(MU_LAW_CLIP) + (MU_LAW_BIAS >> 2);
+    let value = match segment(magnitude, 0x3F) {
+        8 => 0x7F,
+        segment => (segment << 4) | ((magnitude >> (segment + 1)) & 0x0F),
+    };
+    (value ^ mask) as u8
+}
+
+fn decode_mu_law(value: u8) -> i16 {
+    let value = !value as i32;
+    let magnitude = (((value & 0x0F) << 3) + MU_LAW_BIAS) << ((value & 0x70) >> 4);
+    match value & 0x80 != 0 {
+        true => (MU_LAW_BIAS - magnitude) as i16,
+        false => (magnitude - MU_LAW_BIAS) as i16,
+    }
+}
+
+fn encode_a_law(sample: i16) -> u8 {
+    let sample = (sample as i32) >> 3;
+    let (magnitude, mask) = match sample < 0 {
+        true => (-sample - 1, 0x55),
+        false => (sample, 0xD5),
+    };
+    let value = match segment(magnitude, 0x1F) {
+        8 => 0x7F,
+        segment @ 0..=1 => (segment << 4) | ((magnitude >> 1) & 0x0F),
+        segment => (segment << 4) | ((magnitude >> segment) & 0x0F),
+    };
+    (value ^ mask) as u8
+}
+
+fn decode_a_law(value: u8) -> i16 {
+    let value = (value ^ 0x55) as i32;
+    let quantization = (value & 0x0F) << 4;
+    let magnitude = match (value & 0x70) >> 4 {
+        0 => quantization + 8,
+        segment => (quantization + 0x108) << (segment - 1),
+    };
+    match value & 0x80 != 0 {
+        true => magnitude as i16,
+        false => -magnitude as i16,
+    }
+}
@@ -1,8 +1,15 @@
 extern crate sample;
+#[cfg(feature = "serde")]
+extern crate serde;
 
 mod dtmf;
 pub mod decoder;
 pub mod encoder;
+pub mod g711;
+pub mod pcap;
+pub mod rfc4733;
+pub mod sip_info;
 
 // Export the important structs directly into the lib root.
-pub use self::dtmf::{Signal, SignalIterator, SignalParsingError, Message};
+pub use self::dtmf::{Digit, Signal, SignalIterator, SignalParsingError, Message, MessageParseError,
+                     IntoSignalIterator, Element, ElementIterator};
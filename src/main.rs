@@ -7,31 +7,145 @@ extern crate clap;
 
 use clap::{Arg, SubCommand, ArgMatches};
 
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Result as IoResult, Write};
 use std::path::Path;
 use dtmf::Message;
+use dtmf::g711::Law;
+
+/// The sample rate of raw G.711 files.
+const RAW_SAMPLE_RATE: u32 = 8000;
+
+/// Returns the companding law of a raw G.711 file, iff it has the extension `.ul` or `.al`.
+fn raw_law(file: &Path) -> Option<Law> {
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("ul") => Some(Law::MuLaw),
+        Some(extension) if extension.eq_ignore_ascii_case("al") => Some(Law::ALaw),
+        _ => None,
+    }
+}
+
+/// Returns the format tag of a WAV file containing G.711 samples.
+fn format_tag(law: Law) -> u16 {
+    match law {
+        Law::ALaw => 6,
+        Law::MuLaw => 7,
+    }
+}
+
+/// Reads the companding law, the sample rate and the samples of the first channel from a WAV
+/// file, iff it contains G.711 samples, which are not supported by hound.
+fn read_companded_wav(file: &Path) -> IoResult<Option<(Law, u32, Vec<u8>)>> {
+    let invalid = || Error::new(ErrorKind::InvalidData, "Invalid WAV file");
+    let u16_at = |data: &[u8], offset: usize| data[offset] as u16 | (data[offset + 1] as u16) << 8;
+    let u32_at = |data: &[u8], offset: usize| {
+        u16_at(data, offset) as u32 | (u16_at(data, offset + 2) as u32) << 16
+    };
+
+    let mut data = Vec::new();
+    File::open(file)?.read_to_end(&mut data)?;
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Ok(None);
+    }
+
+    // Walk through the chunks, which are padded to an even length.
+    let (mut format, mut samples, mut offset) = (None, None, 12);
+    while offset + 8 <= data.len() {
+        let length = u32_at(&data, offset + 4) as usize;
+        let body = &data[offset + 8..(offset + 8 + length).min(data.len())];
+        match &data[offset..offset + 4] {
+            b"fmt " if body.len() >= 16 => {
+                let law = match u16_at(body, 0) {
+                    6 => Law::ALaw,
+                    7 => Law::MuLaw,
+                    _ => return Ok(None),
+                };
+                format = Some((law, u16_at(body, 2).max(1) as usize, u32_at(body, 4)));
+            }
+            b"data" => samples = Some(body),
+            _ => {}
+        }
+        offset += 8 + length + length % 2;
+    }
+
+    match (format, samples) {
+        (Some((law, channels, sample_rate)), Some(samples)) => {
+            let samples = samples.iter().step_by(channels).cloned().collect();
+            Ok(Some((law, sample_rate, samples)))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Writes G.711 samples into a mono WAV file.
+fn write_companded_wav(file: &Path, law: Law, sample_rate: u32, samples: &[u8]) -> IoResult<()> {
+    let padding = samples.len() % 2;
+    let mut header = Vec::with_capacity(58);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&(50 + samples.len() as u32 + padding as u32).to_le_bytes());
+    header.extend_from_slice(b"WAVEfmt ");
+    header.extend_from_slice(&18u32.to_le_bytes());
+    header.extend_from_slice(&format_tag(law).to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&8u16.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+
+    // Compressed formats require the number of samples in a fact chunk.
+    header.extend_from_slice(b"fact");
+    header.extend_from_slice(&4u32.to_le_bytes());
+    header.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+
+    let mut writer = File::create(file)?;
+    writer.write_all(&header)?;
+    writer.write_all(samples)?;
+    writer.write_all(&[0; 1][..padding])
+}
 
 /// An helper function which contains the encode subcommand.
 fn encode<'a>(arg_parser: &ArgMatches<'a>, arg_encode_parser: &ArgMatches<'a>) {
 
     /// Encodes a message into a file.
-    fn encode_file<P: AsRef<Path>>(file: P, message: Message, sample_rate: u32) -> bool {
+    fn encode_file(file: &Path, message: Message, sample_rate: u32, law: Option<Law>) -> bool {
         use dtmf::encoder::MessageEncoder;
         use hound::{WavWriter, WavSpec};
 
+        // Compand the samples for raw G.711 files and WAV files with G.711 samples.
+        let companded = |law: Law| {
+            MessageEncoder::new(&message, sample_rate as f64)
+                .samples::<i16>()
+                .map(|sample| law.encode(sample))
+                .collect::<Vec<u8>>()
+        };
+        match (raw_law(file), law) {
+            (Some(law), _) => {
+                return File::create(file)
+                    .and_then(|mut writer| writer.write_all(&companded(law)))
+                    .is_ok()
+            }
+            (None, Some(law)) => {
+                return write_companded_wav(file, law, sample_rate, &companded(law)).is_ok()
+            }
+            (None, None) => {}
+        }
+
         // Create metadata for the wav file
         let spec = WavSpec {
             channels: 1,
-            sample_rate: sample_rate,
+            sample_rate,
             bits_per_sample: 32,
             sample_format: hound::SampleFormat::Int,
         };
 
         // Try to create the file
-        match WavWriter::create(&file, spec) {
+        match WavWriter::create(file, spec) {
             Ok(mut writer) => {
                 // Write all the samples
-                for sample in MessageEncoder::new(&message, sample_rate as f64)
-                    .map(|s| sample::conv::f64::to_i32(s[0])) {
+                for sample in MessageEncoder::new(&message, sample_rate as f64).samples::<i32>() {
                     if writer.write_sample(sample).is_err() {
                         return false;
                     }
@@ -44,7 +158,7 @@ fn encode<'a>(arg_parser: &ArgMatches<'a>, arg_encode_parser: &ArgMatches<'a>) {
 
     // Read the input from STDIN
     let mut input = String::new();
-    if let Err(_) = ::std::io::stdin().read_line(&mut input) {
+    if ::std::io::stdin().read_line(&mut input).is_err() {
         println!("[ERROR] Accessing STDIN failed!");
         return;
     }
@@ -52,8 +166,8 @@ fn encode<'a>(arg_parser: &ArgMatches<'a>, arg_encode_parser: &ArgMatches<'a>) {
     // Parse the input into a message
     let mut message = match input.trim().parse::<Message>() {
         Ok(message) => message,
-        Err(_) => {
-            println!("[ERROR] Invalid message!");
+        Err(error) => {
+            println!("[ERROR] Invalid message: {}!", error);
             return;
         }
     };
@@ -62,10 +176,25 @@ fn encode<'a>(arg_parser: &ArgMatches<'a>, arg_encode_parser: &ArgMatches<'a>) {
     message.set_signal_duration(value_t!(arg_parser, "signal", f64).expect("Invalid value"));
     message.set_silence_duration(value_t!(arg_parser, "silence", f64).expect("Invalid value"));
 
+    // Raw G.711 files are always sampled at 8 kHz.
+    let file = Path::new(arg_parser.value_of("file").expect("Valid file"));
+    let mut sample_rate = value_t!(arg_encode_parser, "sample_rate", u32).expect("Invalid value");
+    if raw_law(file).is_some() {
+        if arg_encode_parser.occurrences_of("sample_rate") > 0 && sample_rate != RAW_SAMPLE_RATE {
+            println!("[ERROR] Raw G.711 files require a sample rate of {} Hz!", RAW_SAMPLE_RATE);
+            return;
+        }
+        sample_rate = RAW_SAMPLE_RATE;
+    }
+
+    let law = match arg_encode_parser.value_of("law") {
+        Some("alaw") => Some(Law::ALaw),
+        Some(_) => Some(Law::MuLaw),
+        None => None,
+    };
+
     // Try to encode the message
-    if !encode_file(Path::new(arg_parser.value_of("file").expect("Valid file")),
-                    message,
-                    value_t!(arg_encode_parser, "sample_rate", u32).expect("Invalid value")) {
+    if !encode_file(file, message, sample_rate, law) {
         println!("[ERROR] Writing the file failed. Do you have sufficient rights?")
     }
 }
@@ -74,20 +203,68 @@ fn encode<'a>(arg_parser: &ArgMatches<'a>, arg_encode_parser: &ArgMatches<'a>) {
 fn decode<'a>(arg_parser: &ArgMatches<'a>) {
 
     /// Decodes a message for a file.
-    fn decode_file<P: AsRef<Path>>(file: P, mut message: &mut Message) -> bool {
-        use hound::WavReader;
+    fn decode_file(file: &Path, message: &mut Message) -> bool {
+        use hound::{WavReader, SampleFormat};
         use dtmf::decoder::decode_message;
+        use sample::I24;
+
+        // Expand G.711 samples, which are either raw or within a WAV file.
+        let companded = match raw_law(file) {
+            Some(law) => {
+                let mut samples = Vec::new();
+                File::open(file)
+                    .and_then(|mut reader| reader.read_to_end(&mut samples))
+                    .map(|_| Some((law, RAW_SAMPLE_RATE, samples)))
+            }
+            None => read_companded_wav(file),
+        };
+        match companded {
+            Ok(Some((law, sample_rate, samples))) => {
+                let samples = samples.iter().map(|&sample| law.decode(sample));
+                if let Err(error) = decode_message(samples, message, sample_rate as f64) {
+                    println!("[WARNING] Decoding stopped: {}", error);
+                }
+                return true;
+            }
+            Ok(None) => {}
+            Err(_) => return false,
+        }
 
         // Try to open the file
         match WavReader::open(file) {
             Ok(mut reader) => {
-                let sample_rate = reader.spec().sample_rate as f64;
-                let samples = reader.samples::<i32>().map(|s| match s {
-                    Ok(sample) => sample::conv::i32::to_f64(sample),
-                    Err(_) => 0.,
-                });
+                let spec = reader.spec();
+                let sample_rate = spec.sample_rate as f64;
 
-                decode_message(samples, &mut message, sample_rate);
+                // Decode the samples in their own format, such that they are scaled correctly.
+                let result = match (spec.sample_format, spec.bits_per_sample) {
+                    (SampleFormat::Float, _) => {
+                        let samples = reader.samples::<f32>().map(|s| s.unwrap_or(0.));
+                        decode_message(samples, message, sample_rate)
+                    }
+                    (SampleFormat::Int, 8) => {
+                        let samples = reader.samples::<i8>().map(|s| s.unwrap_or(0));
+                        decode_message(samples, message, sample_rate)
+                    }
+                    (SampleFormat::Int, 16) => {
+                        let samples = reader.samples::<i16>().map(|s| s.unwrap_or(0));
+                        decode_message(samples, message, sample_rate)
+                    }
+                    (SampleFormat::Int, 24) => {
+                        let samples = reader.samples::<i32>()
+                            .map(|s| I24::new_unchecked(s.unwrap_or(0)));
+                        decode_message(samples, message, sample_rate)
+                    }
+                    (SampleFormat::Int, _) => {
+                        let samples = reader.samples::<i32>().map(|s| s.unwrap_or(0));
+                        decode_message(samples, message, sample_rate)
+                    }
+                };
+
+                // Decoding errors still allow printing the signals decoded so far.
+                if let Err(error) = result {
+                    println!("[WARNING] Decoding stopped: {}", error);
+                }
                 true
             }
             Err(_) => false,
@@ -106,10 +283,54 @@ fn decode<'a>(arg_parser: &ArgMatches<'a>) {
     }
 }
 
+/// An helper function which contains the pcap subcommand.
+fn extract<'a>(arg_parser: &ArgMatches<'a>, arg_pcap_parser: &ArgMatches<'a>) {
+    use dtmf::pcap::{extract_digits, DigitSource};
+
+    let file = Path::new(arg_parser.value_of("file").expect("Valid file"));
+    let event_type = value_t!(arg_pcap_parser, "event_type", u8).expect("Invalid value");
+
+    let mut data = Vec::new();
+    if File::open(file).and_then(|mut reader| reader.read_to_end(&mut data)).is_err() {
+        println!("[ERROR] Reading the capture failed. Do it really exist?");
+        return;
+    }
+    let streams = match extract_digits(&data, event_type) {
+        Ok(streams) => streams,
+        Err(error) => {
+            println!("[ERROR] Invalid capture: {}!", error);
+            return;
+        }
+    };
+
+    // Print the signals of each stream together with their time within the capture.
+    for stream in streams {
+        let codec = match stream.law() {
+            Some(Law::MuLaw) => "PCMU",
+            Some(Law::ALaw) => "PCMA",
+            None => "telephone-event",
+        };
+        println!("SSRC 0x{:08X} ({}):", stream.ssrc(), codec);
+        for digit in stream.digits() {
+            let source = match digit.source() {
+                DigitSource::InBand => "in-band",
+                DigitSource::Event => "RFC 4733",
+            };
+            println!("  {:>10.3} s  {}  {:.3} s  {} (RTP timestamp {})",
+                     digit.time(),
+                     digit.signal(),
+                     digit.duration(),
+                     source,
+                     digit.timestamp());
+        }
+    }
+}
+
 fn main() {
     let parser = app_from_crate!()
         .arg(Arg::with_name("file")
-            .help("The wav file which is used to be en- or decoded.")
+            .help("The wav file or the raw G.711 file (.ul, .al) which is used to be en- or \
+                   decoded, or the pcap capture to extract signals from.")
             .value_name("FILE")
             .takes_value(true)
             .required(true))
@@ -119,8 +340,8 @@ fn main() {
             .default_value("0.7")
             .validator(|input| {
                 input.parse::<f64>()
-                    .or_else(|_| Err(String::from("Invalid floating point.")))
-                    .and_then(|_| Ok(()))
+                    .map(|_| ())
+                    .map_err(|_| String::from("Invalid floating point."))
             }))
         .arg(Arg::with_name("silence")
             .help("The duration of the silence between the signals in seconds.")
@@ -128,8 +349,8 @@ fn main() {
             .default_value("0.3")
             .validator(|input| {
                 input.parse::<f64>()
-                    .or_else(|_| Err(String::from("Invalid floating point.")))
-                    .and_then(|_| Ok(()))
+                    .map(|_| ())
+                    .map_err(|_| String::from("Invalid floating point."))
             }))
         .subcommand(SubCommand::with_name("encode")
             .about("Encodes an message which was read from STDIN into a file")
@@ -138,24 +359,47 @@ fn main() {
                 .default_value("44100")
                 .validator(|input| {
                     input.parse::<u32>()
-                        .or_else(|_| Err(String::from("Invalid number for sample rate")))
+                        .map_err(|_| String::from("Invalid number for sample rate"))
                         .and_then(|rate| {
-                            match rate >= 8000 && rate <= 92000 {
+                            match (8000..=92000).contains(&rate) {
                                 true => Ok(()),
                                 false => Err(String::from("Invalid range for the rate")),
                             }
                         })
                 })
+                .takes_value(true))
+            .arg(Arg::with_name("law")
+                .help("Writes a wav file with G.711 samples companded by the given law.")
+                .long("law")
+                .possible_values(&["ulaw", "alaw"])
                 .takes_value(true)))
         .subcommand(SubCommand::with_name("decode")
             .about("Decodes an message from a file and print it to STDOUT"))
+        .subcommand(SubCommand::with_name("pcap")
+            .about("Extracts the signals of all RTP streams within a pcap capture and print them \
+                    to STDOUT")
+            .arg(Arg::with_name("event_type")
+                .help("The RTP payload type of telephone-events.")
+                .default_value("101")
+                .validator(|input| {
+                    input.parse::<u8>()
+                        .map_err(|_| String::from("Invalid payload type"))
+                        .and_then(|payload_type| {
+                            match payload_type < 128 {
+                                true => Ok(()),
+                                false => Err(String::from("Invalid payload type")),
+                            }
+                        })
+                })
+                .takes_value(true)))
         .get_matches();
 
     // Process the subcommands
     match parser.subcommand() {
         // The encode subcommand
-        ("encode", Some(encode_parser)) => encode(&parser, &encode_parser),
+        ("encode", Some(encode_parser)) => encode(&parser, encode_parser),
         ("decode", Some(_)) => decode(&parser),
+        ("pcap", Some(pcap_parser)) => extract(&parser, pcap_parser),
         _ => {
             println!("[ERROR] Please specify a subcommand or use 'help' for further assistance!");
         }
@@ -0,0 +1,479 @@
+//! Extraction of DTMF from captures of RTP streams in the pcap format, like the ones written by
+//! Wireshark or tcpdump.
+//!
+//! RTP packets are read from UDP datagrams over IPv4 or IPv6 and grouped into streams by their
+//! SSRC. G.711 payloads are decoded in-band, while telephone-events are decoded out-of-band
+//! according to RFC 4733. Both are reported per stream and ordered by time.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use Signal;
+use decoder::{DigitEvent, StreamingDecoder};
+use g711::Law;
+use rfc4733::EventDecoder;
+
+/// The payload type, which is usually negotiated for telephone-events.
+pub const DEFAULT_EVENT_PAYLOAD_TYPE: u8 = 101;
+
+/// The clock rate of G.711 and its telephone-events.
+const CLOCK_RATE: u32 = 8000;
+/// The longest gap in seconds, which is filled with silence instead of restarting the decoder.
+const MAX_GAP: u32 = 10 * CLOCK_RATE;
+
+/// The origin of a captured signal.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DigitSource {
+    /// The signal was detected within the audio.
+    InBand,
+    /// The signal was sent as telephone-event.
+    Event,
+}
+
+/// A signal found within a captured RTP stream.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CapturedDigit {
+    signal: Signal,
+    source: DigitSource,
+    timestamp: u32,
+    time: f64,
+    duration: f64,
+}
+
+impl CapturedDigit {
+    /// The signal.
+    pub fn signal(&self) -> Signal {
+        self.signal
+    }
+
+    /// Returns whether the signal was detected in-band or sent as telephone-event.
+    pub fn source(&self) -> DigitSource {
+        self.source
+    }
+
+    /// The RTP timestamp of the start of the signal.
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    /// The start of the signal in seconds since the first packet of the capture.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The duration of the signal in seconds.
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+}
+
+/// A captured RTP stream containing G.711 audio or telephone-events.
+#[derive(Clone, Debug)]
+pub struct RtpStream {
+    ssrc: u32,
+    law: Option<Law>,
+    digits: Vec<CapturedDigit>,
+}
+
+impl RtpStream {
+    /// The synchronization source identifier of the stream.
+    pub fn ssrc(&self) -> u32 {
+        self.ssrc
+    }
+
+    /// The companding law of the audio, iff the stream contains G.711 audio.
+    pub fn law(&self) -> Option<Law> {
+        self.law
+    }
+
+    /// The signals found within the stream ordered by their start.
+    pub fn digits(&self) -> &[CapturedDigit] {
+        &self.digits
+    }
+}
+
+/// An error occurring while reading a capture.
+/// # Example
+/// ```
+/// use dtmf::pcap::{extract_digits, PcapError, DEFAULT_EVENT_PAYLOAD_TYPE};
+///
+/// let mut data = vec![0xD4, 0xC3, 0xB2, 0xA1, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+/// data.extend_from_slice(&[0xFF, 0xFF, 0, 0, 105, 0, 0, 0]);
+/// assert_eq!(extract_digits(&data, DEFAULT_EVENT_PAYLOAD_TYPE).unwrap_err(),
+///            PcapError::UnsupportedLinkType(105));
+///
+/// // An empty Ethernet capture without any records.
+/// data[20] = 1;
+/// assert_eq!(extract_digits(&data, DEFAULT_EVENT_PAYLOAD_TYPE).unwrap().len(), 0);
+///
+/// // A capture, whose only record is cut off.
+/// data.extend_from_slice(&[0; 12]);
+/// assert_eq!(extract_digits(&data, DEFAULT_EVENT_PAYLOAD_TYPE).unwrap_err(),
+///            PcapError::Truncated(24));
+///
+/// assert_eq!(extract_digits(b"RIFF", DEFAULT_EVENT_PAYLOAD_TYPE).unwrap_err(),
+///            PcapError::InvalidHeader);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PcapError {
+    /// The capture does not start with a valid pcap header.
+    InvalidHeader,
+    /// The packets of the capture have a link type, which is not supported.
+    UnsupportedLinkType(u32),
+    /// The packet record at the given offset is cut off.
+    Truncated(usize),
+}
+
+impl Display for PcapError {
+    fn fmt(&self, f: &mut Formatter) -> FormatResult {
+        match *self {
+            PcapError::InvalidHeader => write!(f, "the file is not a pcap capture"),
+            PcapError::UnsupportedLinkType(link_type) => {
+                write!(f, "the link type {} is not supported", link_type)
+            }
+            PcapError::Truncated(offset) => write!(f, "the packet at byte {} is cut off", offset),
+        }
+    }
+}
+
+impl Error for PcapError {}
+
+/// Extracts the signals of all RTP streams within a pcap capture. Telephone-events are expected
+/// with the given payload type, while G.711 audio is recognized by its static payload types.
+///
+/// The streams are returned in the order of their first packet. Captures with Ethernet, Linux
+/// cooked or raw IP packets are supported. Packets, which are no RTP packets of interest, are
+/// skipped.
+/// # Example
+/// ```
+/// use dtmf::{Message, Signal};
+/// use dtmf::encoder::MessageEncoder;
+/// use dtmf::g711::Law;
+/// use dtmf::pcap::{extract_digits, DigitSource, DEFAULT_EVENT_PAYLOAD_TYPE};
+/// use dtmf::rfc4733::EventEncoder;
+///
+/// # // Wraps an RTP packet into Ethernet, IPv4 and UDP headers.
+/// # fn frame(payload_type: u8, timestamp: u32, ssrc: u32, payload: &[u8]) -> Vec<u8> {
+/// #     let mut rtp = vec![0x80, payload_type, 0, 0];
+/// #     rtp.extend_from_slice(&timestamp.to_be_bytes());
+/// #     rtp.extend_from_slice(&ssrc.to_be_bytes());
+/// #     rtp.extend_from_slice(payload);
+/// #     let mut frame = vec![0; 12];
+/// #     frame.extend_from_slice(&[0x08, 0x00, 0x45, 0, 0, 0, 0, 0, 0, 0, 64, 17, 0, 0]);
+/// #     frame.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2, 0x13, 0x88, 0x13, 0x8A]);
+/// #     frame.extend_from_slice(&(8 + rtp.len() as u16).to_be_bytes());
+/// #     frame.extend_from_slice(&[0, 0]);
+/// #     frame.extend_from_slice(&rtp);
+/// #     frame
+/// # }
+/// #
+/// # // Writes a capture of Ethernet frames with the given capture times.
+/// # fn capture(frames: &[(f64, Vec<u8>)]) -> Vec<u8> {
+/// #     let mut data = vec![0xD4, 0xC3, 0xB2, 0xA1, 2, 0, 4, 0];
+/// #     data.extend_from_slice(&[0; 8]);
+/// #     data.extend_from_slice(&65535u32.to_le_bytes());
+/// #     data.extend_from_slice(&1u32.to_le_bytes());
+/// #     for &(time, ref frame) in frames {
+/// #         data.extend_from_slice(&(1_600_000_000 + time as u32).to_le_bytes());
+/// #         data.extend_from_slice(&((time.fract() * 1e6).round() as u32).to_le_bytes());
+/// #         data.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+/// #         data.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+/// #         data.extend_from_slice(frame);
+/// #     }
+/// #     data
+/// # }
+/// #
+/// // The first stream carries the signals in-band as µ-law audio in packets of 20 ms.
+/// let audio = MessageEncoder::new(&"17".parse::<Message>().unwrap(), 8000.)
+///     .samples::<i16>()
+///     .map(|sample| Law::MuLaw.encode(sample))
+///     .collect::<Vec<u8>>();
+/// let mut frames = audio.chunks(160)
+///     .enumerate()
+///     .map(|(i, chunk)| (i as f64 * 0.02, frame(0, 5000 + 160 * i as u32, 0xAAAA, chunk)))
+///     .collect::<Vec<_>>();
+///
+/// // The second stream starts a second later and sends telephone-events.
+/// let message = "#".parse::<Message>().unwrap();
+/// for packet in EventEncoder::new(8000).encode(&message, 100) {
+///     let time = 1. + (packet.timestamp() - 100 + packet.event().duration() as u32) as f64 / 8000.;
+///     frames.push((time, frame(101, packet.timestamp(), 0xBBBB, &packet.to_bytes())));
+/// }
+/// frames.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+///
+/// let streams = extract_digits(&capture(&frames), DEFAULT_EVENT_PAYLOAD_TYPE).unwrap();
+/// assert_eq!(streams.len(), 2);
+///
+/// assert_eq!((streams[0].ssrc(), streams[0].law()), (0xAAAA, Some(Law::MuLaw)));
+/// let digits = streams[0].digits();
+/// assert_eq!(digits.len(), 2);
+/// assert_eq!((digits[1].signal(), digits[1].source()), (Signal::digit(7).unwrap(), DigitSource::InBand));
+/// assert!((digits[1].time() - 1.0).abs() < 0.01);
+/// assert!((digits[1].timestamp() as f64 - 5000. - 8000.).abs() < 80.);
+///
+/// assert_eq!((streams[1].ssrc(), streams[1].law()), (0xBBBB, None));
+/// let digits = streams[1].digits();
+/// assert_eq!((digits[0].signal(), digits[0].source()), (Signal::Hash, DigitSource::Event));
+/// assert_eq!(digits[0].timestamp(), 100);
+/// assert!((digits[0].time() - 1.02).abs() < 1e-6);
+/// assert!((digits[0].duration() - 0.7).abs() < 1e-6);
+/// ```
+pub fn extract_digits(data: &[u8], event_payload_type: u8) -> Result<Vec<RtpStream>, PcapError> {
+    if data.len() < 24 {
+        return Err(PcapError::InvalidHeader);
+    }
+
+    // The magic number reveals the byte order and the resolution of the capture times.
+    let (big_endian, resolution) = match [data[0], data[1], data[2], data[3]] {
+        [0xD4, 0xC3, 0xB2, 0xA1] => (false, 1e-6),
+        [0x4D, 0x3C, 0xB2, 0xA1] => (false, 1e-9),
+        [0xA1, 0xB2, 0xC3, 0xD4] => (true, 1e-6),
+        [0xA1, 0xB2, 0x3C, 0x4D] => (true, 1e-9),
+        _ => return Err(PcapError::InvalidHeader),
+    };
+    let field = |offset: usize| {
+        let bytes = [data[offset], data[offset + 1], data[offset + 2], data[offset + 3]];
+        match big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        }
+    };
+
+    let link_type = field(20);
+    if !LINK_TYPES.contains(&link_type) {
+        return Err(PcapError::UnsupportedLinkType(link_type));
+    }
+
+    let (mut streams, mut origin, mut offset) = (Vec::<StreamState>::new(), None, 24);
+    while offset < data.len() {
+        if offset + 16 > data.len() {
+            return Err(PcapError::Truncated(offset));
+        }
+        let length = field(offset + 8) as usize;
+        let packet = data.get(offset + 16..offset + 16 + length)
+            .ok_or(PcapError::Truncated(offset))?;
+        let time = field(offset) as f64 + field(offset + 4) as f64 * resolution;
+        offset += 16 + length;
+
+        // Times are given relative to the first packet of the capture.
+        let time = time - *origin.get_or_insert(time);
+        let rtp = match datagram(link_type, packet).and_then(RtpPacket::parse) {
+            Some(rtp) => rtp,
+            None => continue,
+        };
+        let law = match rtp.payload_type {
+            0 => Some(Law::MuLaw),
+            8 => Some(Law::ALaw),
+            payload_type if payload_type == event_payload_type => None,
+            _ => continue,
+        };
+
+        let index = match streams.iter().position(|stream| stream.ssrc == rtp.ssrc) {
+            Some(index) => index,
+            None => {
+                streams.push(StreamState::new(rtp.ssrc, rtp.timestamp, time));
+                streams.len() - 1
+            }
+        };
+        match law {
+            Some(law) => streams[index].push_audio(law, rtp.timestamp, rtp.payload),
+            None => streams[index].push_event(rtp.timestamp, rtp.payload),
+        }
+    }
+
+    Ok(streams.into_iter().map(StreamState::finish).collect())
+}
+
+/// The supported link types: Ethernet, raw IP and Linux cooked captures.
+const LINK_TYPES: [u32; 3] = [1, 101, 113];
+
+/// Returns the payload of the UDP datagram within a packet of the given link type, if any.
+fn datagram(link_type: u32, packet: &[u8]) -> Option<&[u8]> {
+    let (mut ether_type, mut ip) = match link_type {
+        1 if packet.len() >= 14 => (u16_at(packet, 12), &packet[14..]),
+        113 if packet.len() >= 16 => (u16_at(packet, 14), &packet[16..]),
+        101 => (0, packet),
+        _ => return None,
+    };
+
+    // Skip VLAN tags.
+    while ether_type == 0x8100 && ip.len() >= 4 {
+        ether_type = u16_at(ip, 2);
+        ip = &ip[4..];
+    }
+
+    let udp = match (ether_type, ip.first().map(|byte| byte >> 4)) {
+        (0x0800, Some(4)) | (0, Some(4)) => {
+            // Fragmented datagrams are skipped.
+            let header = ((ip[0] & 0x0F) as usize) * 4;
+            if ip.len() < 20 || ip[9] != 17 || u16_at(ip, 6) & 0x3FFF != 0 {
+                return None;
+            }
+            ip.get(header..)?
+        }
+        (0x86DD, Some(6)) | (0, Some(6)) if ip.len() >= 40 && ip[6] == 17 => &ip[40..],
+        _ => return None,
+    };
+
+    // The length of the datagram excludes the padding of short Ethernet frames.
+    let length = u16_at(udp, 4) as usize;
+    match udp.len() >= 8 && (8..=udp.len()).contains(&length) {
+        true => Some(&udp[8..length]),
+        false => None,
+    }
+}
+
+/// The fields of an RTP packet, which are required to extract signals.
+struct RtpPacket<'a> {
+    payload_type: u8,
+    timestamp: u32,
+    ssrc: u32,
+    payload: &'a [u8],
+}
+
+impl<'a> RtpPacket<'a> {
+    /// Parses an RTP packet, iff it is one of version 2.
+    fn parse(packet: &'a [u8]) -> Option<RtpPacket<'a>> {
+        if packet.len() < 12 || packet[0] >> 6 != 2 {
+            return None;
+        }
+
+        // Skip the contributing sources, the header extension and the padding.
+        let mut header = 12 + 4 * (packet[0] & 0x0F) as usize;
+        if packet[0] & 0x10 != 0 {
+            header += 4 + 4 * u16_at(packet.get(header..)?, 2) as usize;
+        }
+        let padding = match packet[0] & 0x20 != 0 {
+            true => *packet.last()? as usize,
+            false => 0,
+        };
+        let payload = packet.get(header..packet.len().checked_sub(padding)?)?;
+
+        Some(RtpPacket {
+            payload_type: packet[1] & 0x7F,
+            timestamp: u32_at(packet, 4),
+            ssrc: u32_at(packet, 8),
+            payload,
+        })
+    }
+}
+
+/// The state of a stream while the capture is read.
+struct StreamState {
+    ssrc: u32,
+    law: Option<Law>,
+    timestamp: u32,
+    time: f64,
+    decoder: StreamingDecoder,
+    base: u32,
+    next: u32,
+    events: EventDecoder,
+    digits: Vec<CapturedDigit>,
+}
+
+impl StreamState {
+    /// Creates a stream, whose first packet has the given RTP timestamp and capture time.
+    fn new(ssrc: u32, timestamp: u32, time: f64) -> StreamState {
+        StreamState {
+            ssrc,
+            law: None,
+            timestamp,
+            time,
+            decoder: StreamingDecoder::new(CLOCK_RATE as f64),
+            base: 0,
+            next: 0,
+            events: EventDecoder::new(CLOCK_RATE),
+            digits: Vec::new(),
+        }
+    }
+
+    /// Decodes the G.711 payload of a packet with the given RTP timestamp.
+    fn push_audio(&mut self, law: Law, timestamp: u32, payload: &[u8]) {
+        self.law = Some(law);
+        let position = timestamp.wrapping_sub(self.timestamp);
+        let ahead = position.wrapping_sub(self.next) as i32;
+
+        // Repeated or late samples are dropped, while lost ones are replaced by silence.
+        let payload = match ahead {
+            ahead if ahead < 0 => payload.get(ahead.unsigned_abs() as usize..).unwrap_or(&[]),
+            ahead if ahead as u32 > MAX_GAP => {
+                // Long pauses restart the decoder instead.
+                self.flush();
+                self.base = position;
+                payload
+            }
+            ahead => {
+                self.decode(::std::iter::repeat_n(0, ahead as usize));
+                payload
+            }
+        };
+
+        self.next = position.max(self.next).wrapping_add(payload.len() as u32);
+        self.decode(payload.iter().map(|&sample| law.decode(sample)));
+    }
+
+    /// Decodes the telephone-event within the payload of a packet with the given RTP timestamp.
+    fn push_event(&mut self, timestamp: u32, payload: &[u8]) {
+        if let Ok(events) = self.events.push(timestamp, payload) {
+            for event in events {
+                let position = (event.start_sample() as u32).wrapping_sub(self.timestamp);
+                self.report(event, DigitSource::Event, position);
+            }
+        }
+    }
+
+    /// Adds samples to the in-band decoder.
+    fn decode<S: Iterator<Item = i16>>(&mut self, samples: S) {
+        let events = self.decoder.process(samples).expect("Finite samples");
+        for event in events {
+            let position = self.base.wrapping_add(event.start_sample() as u32);
+            self.report(event, DigitSource::InBand, position);
+        }
+    }
+
+    /// Finishes the in-band decoder, which starts again at the next sample.
+    fn flush(&mut self) {
+        if let Some(event) = self.decoder.finish() {
+            let position = self.base.wrapping_add(event.start_sample() as u32);
+            self.report(event, DigitSource::InBand, position);
+        }
+    }
+
+    /// Adds a signal starting at the given number of samples after the first packet.
+    fn report(&mut self, event: DigitEvent, source: DigitSource, position: u32) {
+        self.digits.push(CapturedDigit {
+            signal: event.signal(),
+            source,
+            timestamp: self.timestamp.wrapping_add(position),
+            time: self.time + position as f64 / CLOCK_RATE as f64,
+            duration: event.duration(),
+        });
+    }
+
+    /// Finishes both decoders and returns the stream.
+    fn finish(mut self) -> RtpStream {
+        self.flush();
+        if let Some(event) = self.events.finish() {
+            let position = (event.start_sample() as u32).wrapping_sub(self.timestamp);
+            self.report(event, DigitSource::Event, position);
+        }
+
+        let mut digits = self.digits;
+        digits.sort_by(|a, b| a.time.partial_cmp(&b.time).expect("Valid time"));
+        RtpStream {
+            ssrc: self.ssrc,
+            law: self.law,
+            digits,
+        }
+    }
+}
+
+/// Reads a big-endian 16-bit number.
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    data.get(offset..offset + 2).map_or(0, |bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads a big-endian 32-bit number.
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    (u16_at(data, offset) as u32) << 16 | u16_at(data, offset + 2) as u32
+}
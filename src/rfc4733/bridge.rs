@@ -0,0 +1,237 @@
+use sample::{Sample, ToSample};
+
+use {Message, Signal};
+use decoder::{AcceptanceLimits, DecodeError, DigitEvent, StreamingDecoder};
+
+use super::{event_code, EventEncoder, EventPacket, TelephoneEvent};
+use super::telephone_event::END_PACKETS;
+
+/// A converter which detects signals within a stream of samples and sends them as telephone-event
+/// packets, like a media gateway does.
+///
+/// A key press is sent as soon as the decoder would accept it and updated at each packet interval
+/// of the encoder. Its end packets are sent once the key is released and the decoder reports it,
+/// such that no packets are sent for signals rejected by the decoder, like speech. A key press,
+/// which is only rejected after it has been sent, is not ended. The durations of key presses
+/// longer than the duration field allows are limited to it.
+/// # Example
+/// ```
+/// use dtmf::{Message, Signal};
+/// use dtmf::encoder::MessageEncoder;
+/// use dtmf::rfc4733::{EventDecoder, EventEncoder, InbandToEvents};
+///
+/// let message = "159".parse::<Message>().unwrap();
+/// let samples = MessageEncoder::new(&message, 8000.).map(|x| x[0]);
+///
+/// let mut converter = InbandToEvents::new(8000., EventEncoder::new(8000), 1000);
+/// let mut packets = converter.process(samples).unwrap();
+/// packets.extend(converter.finish());
+///
+/// let ends = packets.iter().filter(|packet| packet.event().is_end()).count();
+/// assert_eq!(ends, 3 * 3);
+///
+/// let mut decoder = EventDecoder::new(8000);
+/// let mut events = Vec::new();
+/// for packet in &packets {
+///     events.extend(decoder.push(packet.timestamp(), &packet.to_bytes()).unwrap());
+/// }
+///
+/// assert!(events.iter().map(|event| event.signal()).collect::<Message>().eq_ignoring_timing(&message));
+/// assert!((events[1].start_sample() as f64 - 1000. - 8000.).abs() < 80.);
+/// assert!((events[1].duration() - 0.7).abs() < 0.02);
+/// ```
+#[derive(Clone, Debug)]
+pub struct InbandToEvents {
+    decoder: StreamingDecoder,
+    encoder: EventEncoder,
+    sample_rate: f64,
+    timestamp: u32,
+    active: Option<(Signal, usize, usize, u32)>,
+}
+
+impl InbandToEvents {
+    /// Creates a new converter for samples with the given sample rate, whose first sample is sent
+    /// with the given RTP timestamp.
+    pub fn new(sample_rate: f64, encoder: EventEncoder, timestamp: u32) -> InbandToEvents {
+        InbandToEvents::with_limits(sample_rate, AcceptanceLimits::default(), encoder, timestamp)
+    }
+
+    /// Creates a new converter like `new`, which only sends signals within the given limits.
+    /// # Example
+    /// ```
+    /// use dtmf::{Message, Signal};
+    /// use dtmf::encoder::MessageEncoder;
+    /// use dtmf::decoder::AcceptanceLimits;
+    /// use dtmf::rfc4733::{EventEncoder, InbandToEvents};
+    ///
+    /// // The second signal is too short according to Q.24.
+    /// let mut first = Message::new(0.05, 0.05);
+    /// first.enqueue(Signal::digit(4).unwrap());
+    /// let mut second = Message::new(0.015, 0.05);
+    /// second.enqueue(Signal::digit(2).unwrap());
+    /// let samples = MessageEncoder::new(&first, 8000.)
+    ///     .chain(MessageEncoder::new(&second, 8000.))
+    ///     .map(|x| x[0]);
+    ///
+    /// let encoder = EventEncoder::new(8000);
+    /// let mut converter = InbandToEvents::with_limits(8000., AcceptanceLimits::q24(), encoder, 0);
+    /// let mut packets = converter.process(samples).unwrap();
+    /// packets.extend(converter.finish());
+    ///
+    /// assert!(packets.iter().all(|packet| packet.event().signal() == Signal::digit(4)));
+    /// assert_eq!(packets.iter().filter(|packet| packet.event().is_end()).count(), 3);
+    /// ```
+    pub fn with_limits(sample_rate: f64,
+                       limits: AcceptanceLimits,
+                       encoder: EventEncoder,
+                       timestamp: u32)
+                       -> InbandToEvents {
+        InbandToEvents {
+            decoder: StreamingDecoder::with_limits(sample_rate, limits),
+            encoder,
+            sample_rate,
+            timestamp,
+            active: None,
+        }
+    }
+
+    /// Adds a single sample and returns the packets, which have to be sent afterwards.
+    pub fn push<S>(&mut self, sample: S) -> Result<Vec<EventPacket>, DecodeError>
+        where S: Sample + ToSample<f64>
+    {
+        let finished = self.decoder.push(sample)?;
+        let mut packets = Vec::new();
+
+        if let Some((signal, start, end, sent)) = self.active {
+            match self.decoder.current() == Some(signal) {
+                true => {
+                    let end = self.decoder.current_event().map_or(end, |event| event.end_sample());
+                    let duration = self.clock(end - start);
+                    let mut sent = sent;
+                    if duration >= sent + self.encoder.packet_interval() {
+                        packets.push(self.packet(signal, start, duration, false, false));
+                        sent = duration;
+                    }
+                    self.active = Some((signal, start, end, sent));
+                }
+                false => packets.extend(self.end(signal, start, finished)),
+            }
+        }
+
+        if self.active.is_none() {
+            if let Some(event) = self.decoder.current_event() {
+                let (start, end) = (event.start_sample(), event.end_sample());
+                let duration = self.clock(end - start);
+                packets.push(self.packet(event.signal(), start, duration, false, true));
+                self.active = Some((event.signal(), start, end, duration));
+            }
+        }
+        Ok(packets)
+    }
+
+    /// Adds a chunk of samples and returns the packets, which have to be sent afterwards.
+    ///
+    /// The processing stops at the first sample which is not finite.
+    pub fn process<S>(&mut self, samples: S) -> Result<Vec<EventPacket>, DecodeError>
+        where S: IntoIterator,
+              S::Item: Sample + ToSample<f64>
+    {
+        let mut packets = Vec::new();
+        for sample in samples {
+            packets.extend(self.push(sample)?);
+        }
+        Ok(packets)
+    }
+
+    /// Finishes the stream and returns the end packets of the key press in progress, if any.
+    pub fn finish(&mut self) -> Vec<EventPacket> {
+        let finished = self.decoder.finish();
+        match self.active {
+            Some((signal, start, _, _)) => self.end(signal, start, finished),
+            None => Vec::new(),
+        }
+    }
+
+    /// Ends the active key press and returns its end packets, iff the decoder reported it.
+    fn end(&mut self,
+           signal: Signal,
+           start: usize,
+           finished: Option<DigitEvent>)
+           -> Vec<EventPacket> {
+        self.active = None;
+        match finished.filter(|event| event.signal() == signal) {
+            Some(event) => {
+                let duration = self.clock(event.end_sample() - start);
+                vec![self.packet(signal, start, duration, true, false); END_PACKETS]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Creates the packet of a key press starting at the given sample.
+    fn packet(&self,
+              signal: Signal,
+              start: usize,
+              duration: u32,
+              end: bool,
+              marker: bool)
+              -> EventPacket {
+        let event = TelephoneEvent::new(event_code(signal),
+                                        end,
+                                        self.encoder.volume(),
+                                        duration.min(u16::MAX as u32) as u16)
+            .expect("Valid volume");
+        EventPacket::new(self.timestamp.wrapping_add(self.clock(start)), marker, event)
+    }
+
+    /// Converts a number of samples into units of the RTP clock.
+    fn clock(&self, samples: usize) -> u32 {
+        (samples as f64 * self.encoder.clock_rate() as f64 / self.sample_rate) as u32
+    }
+}
+
+/// Creates a message which reproduces the signals and the timing of the given events, such that
+/// they could be played by a `MessageEncoder`. The positions of the events are counted from the
+/// given origin.
+/// # Example
+/// ```
+/// use dtmf::Message;
+/// use dtmf::encoder::MessageEncoder;
+/// use dtmf::decoder::StreamingDecoder;
+/// use dtmf::rfc4733::{message_from_events, EventDecoder, EventEncoder};
+///
+/// let message = "*31#".parse::<Message>().unwrap();
+/// let packets = EventEncoder::new(8000).encode(&message, 123456);
+///
+/// let mut decoder = EventDecoder::new(8000);
+/// let mut events = Vec::new();
+/// for packet in &packets {
+///     events.extend(decoder.push(packet.timestamp(), &packet.to_bytes()).unwrap());
+/// }
+///
+/// // Regenerate the audio starting at the given timestamp.
+/// let regenerated = message_from_events(&events, 123456 - 4000);
+/// assert_eq!(regenerated.to_string(), ",*31#");
+/// assert!((regenerated.duration() - 0.5 - message.duration()).abs() < 1e-9);
+///
+/// let samples = MessageEncoder::new(&regenerated, 8000.).map(|x| x[0]);
+/// let mut decoder = StreamingDecoder::new(8000.);
+/// let mut detected = decoder.process(samples).unwrap();
+/// detected.extend(decoder.finish());
+/// assert_eq!(detected.len(), 4);
+/// assert!((detected[0].start() - 0.5).abs() < 0.01);
+/// ```
+pub fn message_from_events(events: &[DigitEvent], origin: usize) -> Message {
+    let mut message = Message::default();
+    if let Some(first) = events.first() {
+        let lead = first.start_sample().saturating_sub(origin) as f64 / first.sample_rate();
+        if lead > 0.0 {
+            message.enqueue_pause(lead);
+        }
+    }
+    for (index, event) in events.iter().enumerate() {
+        let silence = events.get(index + 1).map_or(0.0, |next| event.gap_to(next).max(0.0));
+        message.enqueue_with_durations(event.signal(), event.duration(), silence);
+    }
+    message
+}
@@ -0,0 +1,129 @@
+use decoder::DigitEvent;
+
+use super::{EventParseError, TelephoneEvent};
+
+/// A decoder which converts received telephone-event packets into the signals they carry.
+///
+/// The positions of the reported events are RTP timestamps. Repeated packets are ignored and
+/// events, whose end packets are lost, are reported once the next event starts. Segments of long
+/// events are joined. Events, which are not DTMF signals like hook flashes, are skipped.
+/// # Example
+/// ```
+/// use dtmf::Signal;
+/// use dtmf::rfc4733::{EventDecoder, TelephoneEvent};
+///
+/// let packet = |code, end, duration| TelephoneEvent::new(code, end, 10, duration).unwrap();
+///
+/// let mut decoder = EventDecoder::new(8000);
+/// assert_eq!(decoder.push(1000, &packet(5, false, 160).to_bytes()), Ok(vec![]));
+///
+/// // The end packets of the first event are lost, while the second event is a single packet.
+/// let events = decoder.push(3000, &packet(3, true, 400).to_bytes()).unwrap();
+/// assert_eq!(events.len(), 2);
+/// assert_eq!(events[0].signal(), Signal::digit(5).unwrap());
+/// assert_eq!((events[0].start_sample(), events[0].end_sample()), (1000, 1160));
+/// assert_eq!(events[1].signal(), Signal::digit(3).unwrap());
+///
+/// // Repeated end packets and hook flashes are skipped.
+/// assert_eq!(decoder.push(3000, &packet(3, true, 400).to_bytes()), Ok(vec![]));
+/// assert_eq!(decoder.push(8000, &packet(16, true, 4800).to_bytes()), Ok(vec![]));
+/// assert_eq!(decoder.finish(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EventDecoder {
+    clock_rate: u32,
+    current: Option<(u32, u32, TelephoneEvent)>,
+    ended: Option<u32>,
+}
+
+impl EventDecoder {
+    /// Creates a new decoder for the given RTP clock rate.
+    pub fn new(clock_rate: u32) -> EventDecoder {
+        EventDecoder {
+            clock_rate,
+            current: None,
+            ended: None,
+        }
+    }
+
+    /// Adds the payload of a packet with the given RTP timestamp and returns the signals, which
+    /// have finished.
+    /// # Example
+    /// ```
+    /// use dtmf::Signal;
+    /// use dtmf::rfc4733::{EventDecoder, TelephoneEvent};
+    ///
+    /// // A long event is split into two segments.
+    /// let mut decoder = EventDecoder::new(8000);
+    /// let first = TelephoneEvent::new(12, false, 10, 0xFFFF).unwrap();
+    /// let second = TelephoneEvent::new(12, true, 10, 1000).unwrap();
+    ///
+    /// assert_eq!(decoder.push(0, &first.to_bytes()), Ok(vec![]));
+    /// let events = decoder.push(0xFFFF, &second.to_bytes()).unwrap();
+    /// assert_eq!(events[0].signal(), Signal::A);
+    /// assert_eq!(events[0].end_sample(), 0xFFFF + 1000);
+    /// ```
+    pub fn push(&mut self,
+                timestamp: u32,
+                payload: &[u8])
+                -> Result<Vec<DigitEvent>, EventParseError> {
+        let event = TelephoneEvent::from_bytes(payload)?;
+        let mut events = Vec::new();
+        if self.ended == Some(timestamp) {
+            return Ok(events);
+        }
+
+        match self.current {
+            Some((start, segment, current)) if segment == timestamp => {
+                let duration = current.duration().max(event.duration());
+                self.current = Some((start, segment, with_duration(event, duration)));
+            }
+            Some((start, segment, current)) if current.code() == event.code() &&
+                                               current.duration() == u16::MAX &&
+                                               segment.wrapping_add(0xFFFF) == timestamp => {
+                self.current = Some((start, timestamp, event));
+            }
+            _ => {
+                events.extend(self.complete());
+                self.current = Some((timestamp, timestamp, event));
+            }
+        }
+
+        if event.is_end() {
+            events.extend(self.complete());
+            self.ended = Some(timestamp);
+        }
+        Ok(events)
+    }
+
+    /// Finishes the stream of packets and returns the signal, whose end packets were not
+    /// received, if any.
+    ///
+    /// Afterwards, the decoder is reset and can be used for another stream.
+    pub fn finish(&mut self) -> Option<DigitEvent> {
+        self.ended = None;
+        self.complete()
+    }
+
+    /// Completes the current event and returns it, iff it is a signal.
+    fn complete(&mut self) -> Option<DigitEvent> {
+        let clock_rate = self.clock_rate as f64;
+        self.current.take().and_then(|(start, segment, event)| {
+            let duration = segment.wrapping_sub(start) as usize + event.duration() as usize;
+            event.signal().map(|signal| {
+                DigitEvent::new(signal, start as usize, start as usize + duration, clock_rate)
+            })
+        })
+    }
+
+    /// The RTP clock rate.
+    pub fn clock_rate(&self) -> u32 {
+        self.clock_rate
+    }
+}
+
+/// Returns the event with the given duration.
+fn with_duration(event: TelephoneEvent, duration: u16) -> TelephoneEvent {
+    TelephoneEvent::new(event.code(), event.is_end(), event.volume(), duration)
+        .expect("Valid volume")
+}
@@ -0,0 +1,164 @@
+use {Element, Message};
+
+use super::{event_code, EventPacket, TelephoneEvent, FLASH_EVENT};
+use super::telephone_event::{END_PACKETS, MAX_VOLUME};
+
+/// An encoder which converts a message into telephone-event packets.
+///
+/// While an event lasts, a packet with its duration so far is sent at each packet interval. The
+/// final packet has the end bit set and is sent three times. Events which last longer than the
+/// duration field allows are split into segments with their own timestamps.
+/// # Example
+/// ```
+/// use dtmf::{Message, Signal};
+/// use dtmf::rfc4733::EventEncoder;
+///
+/// let mut message = Message::new(0.05, 0.05);
+/// message.enqueue(Signal::digit(1).unwrap());
+/// message.enqueue(Signal::digit(2).unwrap());
+///
+/// let packets = EventEncoder::new(8000).encode(&message, 0);
+/// let durations = packets.iter()
+///     .map(|packet| (packet.timestamp(), packet.event().duration(), packet.event().is_end()))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(&durations[..5],
+///            &[(0, 160, false), (0, 320, false), (0, 400, true), (0, 400, true), (0, 400, true)]);
+/// assert_eq!(durations[5], (800, 160, false));
+/// assert!(packets[0].is_marker() && packets[5].is_marker());
+/// assert!(packets.iter().filter(|packet| packet.is_marker()).count() == 2);
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EventEncoder {
+    clock_rate: u32,
+    packet_interval: u32,
+    volume: u8,
+}
+
+impl EventEncoder {
+    /// Creates a new encoder for the given RTP clock rate, which sends a packet each 20 ms with a
+    /// volume of -10 dBm0.
+    pub fn new(clock_rate: u32) -> EventEncoder {
+        EventEncoder {
+            clock_rate,
+            packet_interval: (clock_rate / 50).max(1),
+            volume: 10,
+        }
+    }
+
+    /// Changes the interval between two packets of an event in seconds.
+    pub fn with_packet_interval(&self, interval: f64) -> EventEncoder {
+        EventEncoder {
+            packet_interval: ((interval * self.clock_rate as f64) as u32).max(1),
+            ..*self
+        }
+    }
+
+    /// Changes the volume of the events in -dBm0, iff it is at most 63.
+    pub fn with_volume(&self, volume: u8) -> Option<EventEncoder> {
+        match volume <= MAX_VOLUME {
+            true => Some(EventEncoder { volume, ..*self }),
+            false => None,
+        }
+    }
+
+    /// The RTP clock rate.
+    pub fn clock_rate(&self) -> u32 {
+        self.clock_rate
+    }
+
+    /// The interval between two packets of an event in units of the RTP clock.
+    pub fn packet_interval(&self) -> u32 {
+        self.packet_interval
+    }
+
+    /// The volume of the events in -dBm0.
+    pub fn volume(&self) -> u8 {
+        self.volume
+    }
+
+    /// Encodes a message into packets in the order they are sent. The first signal starts at the
+    /// given RTP timestamp.
+    ///
+    /// The signals are timed like by the `MessageEncoder`, while hook flashes are sent as events
+    /// after the silence of the previous signal.
+    /// # Example
+    /// ```
+    /// use dtmf::Message;
+    /// use dtmf::rfc4733::{EventEncoder, FLASH_EVENT};
+    ///
+    /// let message = "1!".parse::<Message>().unwrap();
+    /// let packets = EventEncoder::new(8000).encode(&message, u32::MAX);
+    /// let flash = packets.last().unwrap();
+    ///
+    /// // Timestamps wrap around. The flash follows the silence after the signal.
+    /// assert_eq!(flash.event().code(), FLASH_EVENT);
+    /// assert_eq!(flash.timestamp(), 7999);
+    /// assert_eq!(flash.event().duration(), 4800);
+    /// ```
+    pub fn encode(&self, message: &Message, timestamp: u32) -> Vec<EventPacket> {
+        let samples = |duration: f64| (duration * self.clock_rate as f64) as u32;
+        let (mut packets, mut time, mut gap) = (Vec::new(), 0u32, 0u32);
+        for element in message.elements() {
+            let (duration, silence) = message.durations(element);
+            let code = match *element {
+                Element::Tone { signal, .. } => event_code(signal),
+                Element::Flash => FLASH_EVENT,
+                _ => {
+                    gap += samples(duration);
+                    continue;
+                }
+            };
+
+            time += gap;
+            self.encode_event(&mut packets, code, timestamp.wrapping_add(time), samples(duration));
+            time += samples(duration);
+            gap = match *element {
+                Element::Flash => 0,
+                _ => samples(silence),
+            };
+        }
+        packets
+    }
+
+    /// Adds the packets of a single event to the given ones.
+    fn encode_event(&self,
+                    packets: &mut Vec<EventPacket>,
+                    code: u8,
+                    timestamp: u32,
+                    length: u32) {
+        let max_duration = u16::MAX as u32;
+        let mut start = 0;
+        loop {
+            // Events longer than the duration field are split into segments.
+            let segment = (length - start).min(max_duration);
+            let last = start + segment == length;
+            let event = |end, duration: u32| {
+                TelephoneEvent::new(code, end, self.volume, duration as u16).expect("Valid volume")
+            };
+
+            let mut duration = self.packet_interval;
+            while duration < segment {
+                packets.push(EventPacket::new(timestamp.wrapping_add(start),
+                                              start == 0 && duration == self.packet_interval,
+                                              event(false, duration)));
+                duration += self.packet_interval;
+            }
+            let repetitions = match last {
+                true => END_PACKETS,
+                false => 1,
+            };
+            for repetition in 0..repetitions {
+                packets.push(EventPacket::new(timestamp.wrapping_add(start),
+                                              start == 0 && segment <= self.packet_interval &&
+                                              repetition == 0,
+                                              event(last, segment)));
+            }
+
+            start += segment;
+            if last {
+                return;
+            }
+        }
+    }
+}
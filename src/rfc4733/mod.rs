@@ -0,0 +1,37 @@
+//! Out-of-band transport of DTMF as RTP telephone-events according to RFC 4733, which replaces
+//! RFC 2833.
+//!
+//! Instead of audio, each key press is sent as a sequence of packets, which share the timestamp of
+//! the start of the event and carry its growing duration in units of the RTP clock. The end of an
+//! event is marked by an end bit and its last packet is repeated to survive packet loss.
+//! # Example
+//! ```
+//! use dtmf::Message;
+//! use dtmf::rfc4733::{EventDecoder, EventEncoder};
+//!
+//! let message = "0800#".parse::<Message>().unwrap();
+//! let packets = EventEncoder::new(8000).encode(&message, 1000);
+//!
+//! let mut decoder = EventDecoder::new(8000);
+//! let mut events = Vec::new();
+//! for packet in &packets {
+//!     events.extend(decoder.push(packet.timestamp(), &packet.to_bytes()).unwrap());
+//! }
+//! events.extend(decoder.finish());
+//!
+//! let signals = events.iter().map(|event| event.signal()).collect::<Message>();
+//! assert!(signals.eq_ignoring_timing(&message));
+//! assert_eq!(events[1].start_sample(), 1000 + 8000);
+//! assert_eq!(events[1].duration(), 0.7);
+//! ```
+
+mod telephone_event;
+mod event_encoder;
+mod event_decoder;
+mod bridge;
+
+pub use self::telephone_event::{TelephoneEvent, EventPacket, EventParseError, FLASH_EVENT,
+                                event_code, event_signal};
+pub use self::event_encoder::EventEncoder;
+pub use self::event_decoder::EventDecoder;
+pub use self::bridge::{InbandToEvents, message_from_events};
@@ -0,0 +1,213 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use Signal;
+
+/// The event code of a hook flash.
+pub const FLASH_EVENT: u8 = 16;
+
+/// The largest volume, which is given in -dBm0.
+pub(crate) const MAX_VOLUME: u8 = 63;
+
+/// The number of times the final packet of an event is sent.
+pub(crate) const END_PACKETS: usize = 3;
+
+/// Returns the event code of a signal.
+/// # Example
+/// ```
+/// use dtmf::Signal;
+/// use dtmf::rfc4733::{event_code, event_signal};
+///
+/// assert_eq!(event_code(Signal::digit(7).unwrap()), 7);
+/// assert_eq!(event_code(Signal::Asterisk), 10);
+/// assert_eq!(event_code(Signal::D), 15);
+///
+/// for &signal in Signal::iter() {
+///     assert_eq!(event_signal(event_code(signal)), Some(signal));
+/// }
+/// assert_eq!(event_signal(16), None);
+/// ```
+pub fn event_code(signal: Signal) -> u8 {
+    match signal {
+        Signal::Digit(digit) => digit.value(),
+        Signal::Asterisk => 10,
+        Signal::Hash => 11,
+        Signal::A => 12,
+        Signal::B => 13,
+        Signal::C => 14,
+        Signal::D => 15,
+    }
+}
+
+/// Returns the signal of an event code, iff it is a DTMF event.
+pub fn event_signal(code: u8) -> Option<Signal> {
+    match code {
+        0..=9 => Signal::digit(code),
+        10 => Some(Signal::Asterisk),
+        11 => Some(Signal::Hash),
+        12 => Some(Signal::A),
+        13 => Some(Signal::B),
+        14 => Some(Signal::C),
+        15 => Some(Signal::D),
+        _ => None,
+    }
+}
+
+/// The payload of a telephone-event packet.
+/// # Example
+/// ```
+/// use dtmf::Signal;
+/// use dtmf::rfc4733::TelephoneEvent;
+///
+/// let event = TelephoneEvent::new(11, true, 10, 800).unwrap();
+/// assert_eq!(event.to_bytes(), [0x0B, 0x8A, 0x03, 0x20]);
+/// assert_eq!(TelephoneEvent::from_bytes(&event.to_bytes()), Ok(event));
+/// assert_eq!(event.signal(), Some(Signal::Hash));
+///
+/// // The volume is limited to 63 dB below 0 dBm0.
+/// assert!(TelephoneEvent::new(11, true, 64, 800).is_none());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TelephoneEvent {
+    code: u8,
+    end: bool,
+    volume: u8,
+    duration: u16,
+}
+
+impl TelephoneEvent {
+    /// Creates a new event with the given code, end bit, volume in -dBm0 and duration in units of
+    /// the RTP clock, iff the volume is at most 63.
+    pub fn new(code: u8, end: bool, volume: u8, duration: u16) -> Option<TelephoneEvent> {
+        match volume <= MAX_VOLUME {
+            true => {
+                Some(TelephoneEvent {
+                    code,
+                    end,
+                    volume,
+                    duration,
+                })
+            }
+            false => None,
+        }
+    }
+
+    /// Parses the payload of a packet. The reserved bit is ignored.
+    /// # Example
+    /// ```
+    /// use dtmf::rfc4733::{EventParseError, TelephoneEvent};
+    ///
+    /// let event = TelephoneEvent::from_bytes(&[0x10, 0x4A, 0x12, 0xC0]).unwrap();
+    /// assert_eq!(event.code(), 16);
+    /// assert!(!event.is_end());
+    /// assert_eq!(event.volume(), 10);
+    /// assert_eq!(event.duration(), 4800);
+    ///
+    /// assert_eq!(TelephoneEvent::from_bytes(&[0x10, 0x4A]), Err(EventParseError::TooShort(2)));
+    /// ```
+    pub fn from_bytes(payload: &[u8]) -> Result<TelephoneEvent, EventParseError> {
+        match payload.len() >= 4 {
+            true => {
+                Ok(TelephoneEvent {
+                    code: payload[0],
+                    end: payload[1] & 0x80 != 0,
+                    volume: payload[1] & MAX_VOLUME,
+                    duration: (payload[2] as u16) << 8 | payload[3] as u16,
+                })
+            }
+            false => Err(EventParseError::TooShort(payload.len())),
+        }
+    }
+
+    /// Returns the payload of a packet.
+    pub fn to_bytes(&self) -> [u8; 4] {
+        let end = match self.end {
+            true => 0x80,
+            false => 0x00,
+        };
+        [self.code, end | self.volume, (self.duration >> 8) as u8, self.duration as u8]
+    }
+
+    /// The code of the event.
+    pub fn code(&self) -> u8 {
+        self.code
+    }
+
+    /// Returns the signal of the event, iff it is a DTMF event.
+    pub fn signal(&self) -> Option<Signal> {
+        event_signal(self.code)
+    }
+
+    /// Returns true, iff the event has ended.
+    pub fn is_end(&self) -> bool {
+        self.end
+    }
+
+    /// The volume of the event in -dBm0.
+    pub fn volume(&self) -> u8 {
+        self.volume
+    }
+
+    /// The duration of the event so far in units of the RTP clock.
+    pub fn duration(&self) -> u16 {
+        self.duration
+    }
+}
+
+/// A telephone-event together with the RTP header fields it depends on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EventPacket {
+    timestamp: u32,
+    marker: bool,
+    event: TelephoneEvent,
+}
+
+impl EventPacket {
+    /// Creates a new packet of an event starting at the given RTP timestamp.
+    pub fn new(timestamp: u32, marker: bool, event: TelephoneEvent) -> EventPacket {
+        EventPacket {
+            timestamp,
+            marker,
+            event,
+        }
+    }
+
+    /// The RTP timestamp of the start of the event.
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    /// Returns true, iff the packet is the first one of an event and sets the RTP marker bit.
+    pub fn is_marker(&self) -> bool {
+        self.marker
+    }
+
+    /// The event carried by the packet.
+    pub fn event(&self) -> TelephoneEvent {
+        self.event
+    }
+
+    /// Returns the payload of the packet.
+    pub fn to_bytes(&self) -> [u8; 4] {
+        self.event.to_bytes()
+    }
+}
+
+/// An error occurring while parsing the payload of a telephone-event.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EventParseError {
+    /// The payload contains only the given number of bytes instead of four.
+    TooShort(usize),
+}
+
+impl Display for EventParseError {
+    fn fmt(&self, f: &mut Formatter) -> FormatResult {
+        match *self {
+            EventParseError::TooShort(length) => {
+                write!(f, "the payload contains {} instead of 4 bytes", length)
+            }
+        }
+    }
+}
+
+impl Error for EventParseError {}
@@ -0,0 +1,236 @@
+//! Transport of DTMF within the bodies of SIP INFO requests.
+//!
+//! Two body formats are common: `application/dtmf-relay` carries a signal together with its
+//! duration in milliseconds, while `application/dtmf` carries only the signal. Each request
+//! transports a single key press.
+//! # Example
+//! ```
+//! use dtmf::Message;
+//! use dtmf::sip_info::{info_digits, message_from_info, InfoDigit, InfoFormat};
+//!
+//! let message = "5#".parse::<Message>().unwrap();
+//! let bodies = info_digits(&message)
+//!     .iter()
+//!     .map(|digit| digit.to_body(InfoFormat::DtmfRelay))
+//!     .collect::<Vec<_>>();
+//! assert_eq!(bodies, ["Signal=5\r\nDuration=700\r\n", "Signal=#\r\nDuration=700\r\n"]);
+//!
+//! let digits = bodies.iter()
+//!     .map(|body| InfoDigit::parse(InfoFormat::DtmfRelay, body).unwrap())
+//!     .collect::<Vec<_>>();
+//! let received = message_from_info(&digits);
+//! assert_eq!(received.to_string(), "5#");
+//! assert_eq!(received.duration(), message.duration());
+//! ```
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+use {Element, Message, Signal};
+use rfc4733::event_signal;
+
+/// The format of the body of a SIP INFO request.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InfoFormat {
+    /// The `application/dtmf-relay` format containing the signal and its duration.
+    DtmfRelay,
+    /// The `application/dtmf` format containing only the signal.
+    Dtmf,
+}
+
+impl InfoFormat {
+    /// Returns the format of the given content type, iff it is supported. Parameters of the
+    /// content type are ignored.
+    /// # Example
+    /// ```
+    /// use dtmf::sip_info::InfoFormat;
+    ///
+    /// assert_eq!(InfoFormat::from_content_type("Application/DTMF-Relay"),
+    ///            Some(InfoFormat::DtmfRelay));
+    /// assert_eq!(InfoFormat::from_content_type("application/dtmf; charset=utf-8"),
+    ///            Some(InfoFormat::Dtmf));
+    /// assert_eq!(InfoFormat::from_content_type("application/sdp"), None);
+    /// ```
+    pub fn from_content_type(content_type: &str) -> Option<InfoFormat> {
+        let media_type = content_type.split(';').next().unwrap_or("").trim();
+        match media_type {
+            _ if media_type.eq_ignore_ascii_case("application/dtmf-relay") => {
+                Some(InfoFormat::DtmfRelay)
+            }
+            _ if media_type.eq_ignore_ascii_case("application/dtmf") => Some(InfoFormat::Dtmf),
+            _ => None,
+        }
+    }
+
+    /// Returns the content type of the format.
+    pub fn content_type(&self) -> &'static str {
+        match *self {
+            InfoFormat::DtmfRelay => "application/dtmf-relay",
+            InfoFormat::Dtmf => "application/dtmf",
+        }
+    }
+}
+
+/// A key press transported within the body of a SIP INFO request.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct InfoDigit {
+    signal: Signal,
+    duration: Option<f64>,
+}
+
+impl InfoDigit {
+    /// Creates a new key press of the signal with the given duration in seconds, if known.
+    pub fn new(signal: Signal, duration: Option<f64>) -> InfoDigit {
+        InfoDigit { signal, duration }
+    }
+
+    /// Parses the body of a request in the given format.
+    ///
+    /// Signals are accepted as characters or as event codes like within telephone-events. Unknown
+    /// lines of `application/dtmf-relay` bodies are ignored.
+    /// # Example
+    /// ```
+    /// use dtmf::Signal;
+    /// use dtmf::sip_info::{InfoDigit, InfoFormat, InfoParseError};
+    ///
+    /// let digit = InfoDigit::parse(InfoFormat::DtmfRelay, "Signal=5\r\nDuration=160").unwrap();
+    /// assert_eq!(digit.signal(), Signal::digit(5).unwrap());
+    /// assert_eq!(digit.duration(), Some(0.16));
+    ///
+    /// let digit = InfoDigit::parse(InfoFormat::DtmfRelay, "signal = 11\nduration = 250\n").unwrap();
+    /// assert_eq!(digit, InfoDigit::new(Signal::Hash, Some(0.25)));
+    ///
+    /// let digit = InfoDigit::parse(InfoFormat::Dtmf, "*\r\n").unwrap();
+    /// assert_eq!(digit, InfoDigit::new(Signal::Asterisk, None));
+    ///
+    /// assert_eq!(InfoDigit::parse(InfoFormat::DtmfRelay, "Duration=160"),
+    ///            Err(InfoParseError::MissingSignal));
+    /// assert_eq!(InfoDigit::parse(InfoFormat::DtmfRelay, "Signal=16"),
+    ///            Err(InfoParseError::InvalidSignal(String::from("16"))));
+    /// assert_eq!(InfoDigit::parse(InfoFormat::DtmfRelay, "Signal=1\r\nDuration=-5"),
+    ///            Err(InfoParseError::InvalidDuration(String::from("-5"))));
+    /// ```
+    pub fn parse(format: InfoFormat, body: &str) -> Result<InfoDigit, InfoParseError> {
+        if format == InfoFormat::Dtmf {
+            return parse_signal(body.trim()).map(|signal| InfoDigit::new(signal, None));
+        }
+
+        let (mut signal, mut duration) = (None, None);
+        for line in body.lines() {
+            let mut parts = line.splitn(2, '=');
+            let (key, value) = match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => (key.trim(), value.trim()),
+                _ => continue,
+            };
+            if key.eq_ignore_ascii_case("Signal") {
+                signal = Some(parse_signal(value)?);
+            } else if key.eq_ignore_ascii_case("Duration") {
+                let milliseconds = value.parse::<u32>()
+                    .map_err(|_| InfoParseError::InvalidDuration(String::from(value)))?;
+                duration = Some(milliseconds as f64 / 1000.0);
+            }
+        }
+
+        match signal {
+            Some(signal) => Ok(InfoDigit::new(signal, duration)),
+            None => Err(InfoParseError::MissingSignal),
+        }
+    }
+
+    /// Returns the body of a request in the given format. Durations are rounded to milliseconds.
+    /// # Example
+    /// ```
+    /// use dtmf::Signal;
+    /// use dtmf::sip_info::{InfoDigit, InfoFormat};
+    ///
+    /// let digit = InfoDigit::new(Signal::A, Some(0.1234));
+    /// assert_eq!(digit.to_body(InfoFormat::DtmfRelay), "Signal=A\r\nDuration=123\r\n");
+    /// assert_eq!(digit.to_body(InfoFormat::Dtmf), "A");
+    ///
+    /// let digit = InfoDigit::new(Signal::digit(0).unwrap(), None);
+    /// assert_eq!(digit.to_body(InfoFormat::DtmfRelay), "Signal=0\r\n");
+    /// ```
+    pub fn to_body(&self, format: InfoFormat) -> String {
+        match (format, self.duration) {
+            (InfoFormat::Dtmf, _) => self.signal.to_string(),
+            (InfoFormat::DtmfRelay, Some(duration)) => {
+                format!("Signal={}\r\nDuration={}\r\n",
+                        self.signal,
+                        (duration * 1000.0).round() as u32)
+            }
+            (InfoFormat::DtmfRelay, None) => format!("Signal={}\r\n", self.signal),
+        }
+    }
+
+    /// The signal.
+    pub fn signal(&self) -> Signal {
+        self.signal
+    }
+
+    /// The duration of the key press in seconds, if known.
+    pub fn duration(&self) -> Option<f64> {
+        self.duration
+    }
+}
+
+/// Creates a message of the given key presses. Missing durations and the silence between the
+/// signals are taken from the default message.
+pub fn message_from_info(digits: &[InfoDigit]) -> Message {
+    let mut message = Message::default();
+    for digit in digits {
+        message.enqueue_element(Element::Tone {
+            signal: digit.signal,
+            duration: digit.duration,
+            silence: None,
+        });
+    }
+    message
+}
+
+/// Returns the key presses of the signals within a message together with their durations, such
+/// that each one could be sent as a SIP INFO request. Pauses, waits and hook flashes are skipped.
+pub fn info_digits(message: &Message) -> Vec<InfoDigit> {
+    message.elements()
+        .filter_map(|element| {
+            element.signal()
+                .map(|signal| InfoDigit::new(signal, Some(message.durations(element).0)))
+        })
+        .collect()
+}
+
+/// Parses a signal given as character or as event code.
+fn parse_signal(value: &str) -> Result<Signal, InfoParseError> {
+    let mut chars = value.chars();
+    let signal = match (chars.next(), chars.next()) {
+        (Some(character), None) => Signal::from_char(character).ok(),
+        _ => value.parse::<u8>().ok().and_then(event_signal),
+    };
+    signal.ok_or_else(|| InfoParseError::InvalidSignal(String::from(value)))
+}
+
+/// An error occurring while parsing the body of a SIP INFO request.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum InfoParseError {
+    /// The body does not contain a signal.
+    MissingSignal,
+    /// The given value is not a valid signal.
+    InvalidSignal(String),
+    /// The given value is not a valid duration in milliseconds.
+    InvalidDuration(String),
+}
+
+impl Display for InfoParseError {
+    fn fmt(&self, f: &mut Formatter) -> FormatResult {
+        match *self {
+            InfoParseError::MissingSignal => write!(f, "the body does not contain a signal"),
+            InfoParseError::InvalidSignal(ref value) => {
+                write!(f, "'{}' is not a valid signal", value)
+            }
+            InfoParseError::InvalidDuration(ref value) => {
+                write!(f, "'{}' is not a valid duration", value)
+            }
+        }
+    }
+}
+
+impl Error for InfoParseError {}